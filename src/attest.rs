@@ -0,0 +1,73 @@
+use std::io::Read;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use didkemb::cli::Args;
+use didkemb::digest::{HashAlgorithm, PayloadDigest};
+use didkemb::issuer::{IssueRequest, Issuer};
+use didkemb::DIDExampleStatic;
+
+// attest [--file PATH] [--algorithm sha-256|sha3-256|blake3] [--subject ID]
+//   firma una credenziale che attesta il digest del file (o di stdin)
+// attest --verify JWT_FILE [--file PATH]
+//   verifica la credenziale e che il payload corrisponda al digest attestato
+fn main() -> ExitCode {
+    match didkemb::runtime::block_on(run(Args::from_env())) {
+        Ok(Ok(code)) => code,
+        Ok(Err(e)) | Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn payload(args: &Args) -> didkemb::Result<Box<dyn Read>> {
+    Ok(match args.value("file") {
+        Some(path) => Box::new(
+            std::fs::File::open(path)
+                .map_err(|e| didkemb::Error::Payload(format!("{}: {}", path, e)))?,
+        ),
+        None => Box::new(std::io::stdin().lock()),
+    })
+}
+
+async fn run(args: Args) -> didkemb::Result<ExitCode> {
+    if let Some(jwt_path) = args.value("verify") {
+        let jwt = std::fs::read_to_string(jwt_path)
+            .map_err(|e| didkemb::Error::Usage(format!("{}: {}", jwt_path, e)))?;
+        let mut context_loader = didkemb::context::loader();
+        let report = didkemb::report::verify_jwt_with_payload(
+            jwt.trim(),
+            payload(&args)?,
+            None,
+            &DIDExampleStatic,
+            &mut context_loader,
+        )
+        .await;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(if report.verified {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        });
+    }
+
+    let algorithm: HashAlgorithm = args.parse_or("algorithm", HashAlgorithm::default())?;
+    let digest = PayloadDigest::of_reader(algorithm, payload(&args)?)?;
+    let issuer = Issuer::new(
+        didkemb::issuer_key()?,
+        didkemb::ISSUER_DID,
+        didkemb::VERIFICATION_METHOD,
+        Arc::new(DIDExampleStatic),
+    );
+    let req = IssueRequest {
+        subject: args
+            .value("subject")
+            .map(str::to_string)
+            .unwrap_or_else(|| "urn:uuid:".to_string() + &uuid::Uuid::new_v4().to_string()),
+        claims: digest.claims(),
+        template: None,
+    };
+    println!("{}", issuer.issue(&req).await?);
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,247 @@
+//! Accreditation chains, manufacturer → gateway → device: an accreditation
+//! credential authorizes its subject DID to issue the types listed in
+//! `accreditedFor`, and a credential is trusted when its issuer's
+//! accreditations lead to a configured root of trust.
+
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ssi::jsonld::ContextLoader;
+use ssi::vc::Credential;
+use ssi_dids::did_resolve::DIDResolver;
+
+use crate::datamodel;
+use crate::report::{self, VerificationReport};
+use crate::Result;
+
+pub const ACCREDITATION: &str = "AccreditationCredential";
+pub const DEFAULT_MAX_DEPTH: usize = 3;
+
+/// Unsigned accreditation of `subordinate` by `accreditor` for `types`,
+/// valid until `valid_until` if given. List [`ACCREDITATION`] among `types`
+/// to let the subordinate accredit others in turn.
+pub fn accreditation(
+    accreditor: &str,
+    subordinate: &str,
+    types: &[&str],
+    valid_until: Option<DateTime<Utc>>,
+) -> Result<Credential> {
+    let mut vc = serde_json::json!({
+        "@context": crate::benchmark_context(),
+        "type": ["VerifiableCredential", ACCREDITATION],
+        "issuer": accreditor,
+        "issuanceDate": ssi::ldp::now_ns(),
+        "credentialSubject": {
+            "id": subordinate,
+            "accreditedFor": types,
+        },
+    });
+    if let Some(until) = valid_until {
+        vc["expirationDate"] = until.to_rfc3339().into();
+    }
+    Ok(serde_json::from_value(vc)?)
+}
+
+fn types(vc: &Credential) -> Vec<String> {
+    (&vc.type_)
+        .into_iter()
+        .filter(|t| *t != "VerifiableCredential")
+        .cloned()
+        .collect()
+}
+
+fn issuer(vc: &Credential) -> String {
+    vc.issuer.as_ref().map(|i| i.get_id()).unwrap_or_default()
+}
+
+fn issuance(vc: &Credential) -> Option<DateTime<Utc>> {
+    vc.issuance_date.clone().map(DateTime::from)
+}
+
+fn subject(vc: &Credential) -> Option<(String, Vec<String>)> {
+    let subject = vc.credential_subject.first()?;
+    let id = subject.id.as_ref()?.to_string();
+    let accredited_for = subject
+        .property_set
+        .as_ref()?
+        .get("accreditedFor")?
+        .as_array()?
+        .iter()
+        .filter_map(Value::as_str)
+        .map(str::to_string)
+        .collect();
+    Some((id, accredited_for))
+}
+
+/// Types its issuer needs accreditation for: the credential's own, and for
+/// an accreditation, every type it passes on.
+fn needed(vc: &Credential) -> Vec<String> {
+    let mut needed = types(vc);
+    if needed.iter().any(|t| t == ACCREDITATION) {
+        needed.extend(subject(vc).map(|(_, types)| types).unwrap_or_default());
+    }
+    needed
+}
+
+/// Body of `POST /verify-chain`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ChainRequest {
+    pub jwt: String,
+    #[serde(default)]
+    pub accreditations: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainReport {
+    pub verified: bool,
+    /// The root of trust the chain ends at.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+    /// The credential's report, then one per accreditation up the chain.
+    pub links: Vec<VerificationReport>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl ChainReport {
+    fn fail(mut self, reason: impl Into<String>) -> Self {
+        self.verified = false;
+        self.reason = Some(reason.into());
+        self
+    }
+}
+
+impl fmt::Display for ChainReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.root, &self.reason) {
+            (Some(root), None) => write!(f, "verified up to {}", root),
+            (_, Some(reason)) => write!(f, "chain broken: {}", reason),
+            (None, None) => write!(f, "chain not verified"),
+        }
+    }
+}
+
+/// Walks from a credential up its issuers' accreditations to a root of
+/// trust. Every link is verified like a single credential; besides, each
+/// accreditation must cover the types of the credential below it, have been
+/// in effect when that credential was issued, and the chain must hold at
+/// most `max_depth` accreditations.
+#[derive(Clone)]
+pub struct ChainVerifier {
+    roots: Vec<String>,
+    max_depth: usize,
+    resolver: Arc<dyn DIDResolver + Send>,
+    context_loader: ContextLoader,
+}
+
+impl ChainVerifier {
+    pub fn new(roots: Vec<String>, resolver: Arc<dyn DIDResolver + Send>) -> Self {
+        ChainVerifier {
+            roots,
+            max_depth: DEFAULT_MAX_DEPTH,
+            resolver,
+            context_loader: crate::context::loader(),
+        }
+    }
+
+    pub fn with_context_loader(mut self, context_loader: ContextLoader) -> Self {
+        self.context_loader = context_loader;
+        self
+    }
+
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Verifies the JWT credential `jwt` against `accreditations` (JWTs, in
+    /// any order), as sent along by the issuing gateway.
+    pub async fn verify(&self, jwt: &str, accreditations: &[String]) -> ChainReport {
+        let mut chain = ChainReport::default();
+        let candidates: Vec<(&str, Credential)> = accreditations
+            .iter()
+            .filter_map(|a| Some((a.as_str(), datamodel::credential_unverified(a).ok()?)))
+            .filter(|(_, vc)| types(vc).iter().any(|t| t == ACCREDITATION))
+            .collect();
+        let mut current = match datamodel::credential_unverified(jwt) {
+            Ok(vc) => (jwt, vc),
+            Err(e) => return chain.fail(e),
+        };
+        loop {
+            let mut context_loader = self.context_loader.clone();
+            let link =
+                report::verify_jwt(current.0, None, self.resolver.as_ref(), &mut context_loader)
+                    .await;
+            let verified = link.verified;
+            chain.links.push(link);
+            let issuer = issuer(&current.1);
+            if !verified {
+                return chain.fail(format!("Credential issued by {} not verified", issuer));
+            }
+            if self.roots.contains(&issuer) {
+                chain.verified = true;
+                chain.root = Some(issuer);
+                return chain;
+            }
+            if chain.links.len() > self.max_depth {
+                return chain.fail(format!(
+                    "No root of trust within {} accreditations",
+                    self.max_depth
+                ));
+            }
+            current = match accreditation_of(&issuer, &current.1, &candidates) {
+                Ok((jwt, vc)) => (jwt, vc.clone()),
+                Err(reason) => return chain.fail(reason),
+            };
+        }
+    }
+}
+
+/// The accreditation authorizing `issuer` to issue `vc`, or why there is
+/// none.
+fn accreditation_of<'a>(
+    issuer: &str,
+    vc: &Credential,
+    candidates: &'a [(&'a str, Credential)],
+) -> Result<(&'a str, &'a Credential), String> {
+    let needed = needed(vc);
+    let issued = issuance(vc);
+    let mut reason = format!("No accreditation for {}", issuer);
+    for (jwt, accreditation) in candidates {
+        let Some((subject, accredited_for)) = subject(accreditation) else {
+            continue;
+        };
+        if subject != issuer {
+            continue;
+        }
+        if let Some(denied) = needed.iter().find(|t| !accredited_for.contains(t)) {
+            reason = format!("{} is not accredited to issue {}", issuer, denied);
+            continue;
+        }
+        let from = issuance(accreditation);
+        let until = accreditation
+            .expiration_date
+            .clone()
+            .map(DateTime::<Utc>::from);
+        let in_effect = match issued {
+            Some(issued) => {
+                from.is_none_or(|from| from <= issued) && until.is_none_or(|until| issued <= until)
+            }
+            None => false,
+        };
+        if !in_effect {
+            reason = format!(
+                "Accreditation of {} was not in effect when it issued {}",
+                issuer,
+                needed.join(", ")
+            );
+            continue;
+        }
+        return Ok((jwt, accreditation));
+    }
+    Err(reason)
+}
@@ -0,0 +1,61 @@
+//! Tiny `--flag value` argument parsing for the binaries.
+
+use std::str::FromStr;
+
+use crate::{Error, Result};
+
+pub struct Args {
+    args: Vec<String>,
+}
+
+impl Args {
+    pub fn from_env() -> Self {
+        Args {
+            args: std::env::args().skip(1).collect(),
+        }
+    }
+
+    pub fn new(args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Args {
+            args: args.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Value following `--name`, if present.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        let flag = format!("--{}", name);
+        self.args
+            .iter()
+            .position(|a| *a == flag)
+            .and_then(|i| self.args.get(i + 1))
+            .map(String::as_str)
+    }
+
+    /// Values following every `--name`, for repeatable options.
+    pub fn values(&self, name: &str) -> Vec<&str> {
+        let flag = format!("--{}", name);
+        self.args
+            .windows(2)
+            .filter(|pair| pair[0] == flag)
+            .map(|pair| pair[1].as_str())
+            .collect()
+    }
+
+    /// Parsed value following `--name`, or `default` if absent.
+    pub fn parse_or<T: FromStr>(&self, name: &str, default: T) -> Result<T>
+    where
+        T::Err: std::fmt::Display,
+    {
+        match self.value(name) {
+            Some(v) => v
+                .parse()
+                .map_err(|e| Error::Usage(format!("--{} {}: {}", name, v, e))),
+            None => Ok(default),
+        }
+    }
+
+    /// Whether the bare `--name` switch is present.
+    pub fn flag(&self, name: &str) -> bool {
+        self.args.contains(&format!("--{}", name))
+    }
+}
@@ -0,0 +1,419 @@
+//! CoAP (RFC 7252) transport for issuing credentials and submitting
+//! presentations over UDP, with block-wise transfer (RFC 7959) so JWTs larger
+//! than one datagram fit constrained links.
+//!
+//! - `POST /issue` takes an [`IssueRequest`] as JSON and answers with the JWT.
+//! - `POST /present` takes a JWT credential or presentation and answers with
+//!   the [`VerificationReport`] as JSON.
+//!
+//! The server handles each datagram in its own task, and answers a
+//! retransmitted request with the response it already sent. At most
+//! [`MAX_RESPONSES`] responses are kept, the oldest evicted first.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use coap_lite::block_handler::{BlockHandler, BlockHandlerConfig, BlockValue};
+use coap_lite::{
+    CoapOption, CoapRequest, ContentFormat, MessageClass, MessageType, Packet, RequestType,
+    ResponseType,
+};
+use tokio::net::UdpSocket;
+
+use crate::issuer::{IssueRequest, Issuer};
+use crate::report::VerificationReport;
+use crate::verifier::Verifier;
+use crate::{Error, Result};
+
+/// Largest datagram we read; well above any block we negotiate.
+const MAX_DATAGRAM: usize = 2048;
+
+/// RFC 7252 defaults.
+const ACK_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_RETRANSMIT: u32 = 4;
+/// How long a response is kept to answer retransmissions of its request
+/// (`MAX_TRANSMIT_SPAN`).
+const RESPONSE_LIFETIME: Duration = Duration::from_secs(45);
+/// Most responses kept for retransmissions, whatever their age.
+pub const MAX_RESPONSES: usize = 1024;
+
+/// Block size used by the client unless told otherwise; 6LoWPAN links
+/// typically want 64 or 128.
+pub const DEFAULT_BLOCK_SIZE: usize = 256;
+
+fn transport(e: impl std::fmt::Display) -> Error {
+    Error::Transport(e.to_string())
+}
+
+pub struct CoapServer {
+    issuer: Issuer,
+    verifier: Verifier,
+    blocks: Mutex<BlockHandler<SocketAddr>>,
+    /// Recent requests by peer and message id.
+    responses: Mutex<HashMap<(SocketAddr, u16), Response>>,
+}
+
+/// The reply to a recent request; `None` while the request is handled.
+struct Response {
+    received: Instant,
+    reply: Option<Vec<u8>>,
+}
+
+impl CoapServer {
+    /// `max_message_size` bounds every datagram the server sends; larger
+    /// responses are served block-wise.
+    pub fn new(issuer: Issuer, verifier: Verifier, max_message_size: usize) -> Self {
+        CoapServer {
+            issuer,
+            verifier,
+            blocks: Mutex::new(BlockHandler::new(BlockHandlerConfig {
+                max_total_message_size: max_message_size,
+                ..Default::default()
+            })),
+            responses: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn route(
+        &self,
+        method: RequestType,
+        path: &str,
+        payload: &[u8],
+    ) -> (ResponseType, Vec<u8>) {
+        match (method, path) {
+            (RequestType::Post, "issue") => {
+                let req: IssueRequest = match serde_json::from_slice(payload) {
+                    Ok(req) => req,
+                    Err(e) => return (ResponseType::BadRequest, e.to_string().into_bytes()),
+                };
+                match self.issuer.issue(&req).await {
+                    Ok(jwt) => (ResponseType::Content, jwt.into_bytes()),
+                    Err(e @ Error::Template(_)) => {
+                        (ResponseType::BadRequest, e.to_string().into_bytes())
+                    }
+                    Err(e) => (
+                        ResponseType::InternalServerError,
+                        e.to_string().into_bytes(),
+                    ),
+                }
+            }
+            (RequestType::Post, "present") => {
+                let jwt = String::from_utf8_lossy(payload);
+                let report = self.verifier.verify(jwt.trim()).await;
+                match serde_json::to_vec(&report) {
+                    Ok(body) => (ResponseType::Content, body),
+                    Err(e) => (
+                        ResponseType::InternalServerError,
+                        e.to_string().into_bytes(),
+                    ),
+                }
+            }
+            _ => (ResponseType::NotFound, Vec::new()),
+        }
+    }
+
+    /// Handles one datagram and returns the reply, if any. A retransmission
+    /// gets the reply sent the first time, or none while that is pending.
+    async fn handle(&self, buf: &[u8], src: SocketAddr) -> Option<Vec<u8>> {
+        let packet = Packet::from_bytes(buf).ok()?;
+        if !matches!(packet.header.code, MessageClass::Request(_)) {
+            return None;
+        }
+        let exchange = (src, packet.header.message_id);
+        {
+            let mut responses = self.responses.lock().unwrap();
+            let now = Instant::now();
+            responses.retain(|_, r| now.duration_since(r.received) < RESPONSE_LIFETIME);
+            if let Some(response) = responses.get(&exchange) {
+                return response.reply.clone();
+            }
+            if responses.len() >= MAX_RESPONSES {
+                let oldest = responses
+                    .iter()
+                    .min_by_key(|(_, r)| r.received)
+                    .map(|(exchange, _)| *exchange);
+                if let Some(oldest) = oldest {
+                    responses.remove(&oldest);
+                }
+            }
+            responses.insert(
+                exchange,
+                Response {
+                    received: now,
+                    reply: None,
+                },
+            );
+        }
+        let reply = self.respond(packet, src).await;
+        if let Some(response) = self.responses.lock().unwrap().get_mut(&exchange) {
+            response.reply.clone_from(&reply);
+        }
+        reply
+    }
+
+    async fn respond(&self, packet: Packet, src: SocketAddr) -> Option<Vec<u8>> {
+        let mut request = CoapRequest::from_packet(packet, src);
+        let intercepted = self.blocks.lock().unwrap().intercept_request(&mut request);
+        let handled = match intercepted {
+            Ok(handled) => handled,
+            Err(e) => request.apply_from_error(e),
+        };
+        if !handled {
+            let method = *request.get_method();
+            let path = request.get_path();
+            let (status, body) = self.route(method, &path, &request.message.payload).await;
+            if let Some(ref mut response) = request.response {
+                response.set_status(status);
+                response.message.payload = body;
+                if status == ResponseType::Content && path == "present" {
+                    response
+                        .message
+                        .set_content_format(ContentFormat::ApplicationJSON);
+                }
+            }
+            // The block handler cannot split an empty body into blocks.
+            let empty = request
+                .response
+                .as_ref()
+                .is_none_or(|r| r.message.payload.is_empty());
+            if !empty {
+                let intercepted = self.blocks.lock().unwrap().intercept_response(&mut request);
+                if let Err(e) = intercepted {
+                    request.apply_from_error(e);
+                }
+            }
+        }
+        request.response?.message.to_bytes().ok()
+    }
+}
+
+/// Binds `addr` and returns the bound address with the future running the server.
+pub async fn serve(
+    addr: SocketAddr,
+    server: CoapServer,
+) -> Result<(SocketAddr, impl Future<Output = Result<()>>)> {
+    let socket = Arc::new(UdpSocket::bind(addr).await.map_err(transport)?);
+    let local_addr = socket.local_addr().map_err(transport)?;
+    let server = Arc::new(server);
+    Ok((local_addr, async move {
+        let mut buf = [0u8; MAX_DATAGRAM];
+        loop {
+            let (len, src) = socket.recv_from(&mut buf).await.map_err(transport)?;
+            let (socket, server, datagram) = (socket.clone(), server.clone(), buf[..len].to_vec());
+            tokio::spawn(async move {
+                if let Some(reply) = server.handle(&datagram, src).await {
+                    // A lost reply is sent again when the client retransmits.
+                    let _ = socket.send_to(&reply, src).await;
+                }
+            });
+        }
+    }))
+}
+
+/// Outcome of one (possibly block-wise) request.
+#[derive(Debug, Clone)]
+pub struct Exchange {
+    pub status: ResponseType,
+    pub payload: Vec<u8>,
+    /// Time from the first block sent to the last block received.
+    pub rtt: Duration,
+    /// Block1 requests sent, 1 if the request fit in a single datagram.
+    pub blocks_sent: usize,
+    /// Block2 responses received, 1 if the response fit in a single datagram.
+    pub blocks_received: usize,
+    pub retransmissions: u32,
+}
+
+pub struct CoapClient {
+    socket: UdpSocket,
+    server: SocketAddr,
+    block_size: usize,
+    message_id: AtomicU16,
+}
+
+impl CoapClient {
+    /// `block_size` must be a power of two between 16 and 1024.
+    pub async fn connect(server: SocketAddr, block_size: usize) -> Result<Self> {
+        if !block_size.is_power_of_two() || !(16..=1024).contains(&block_size) {
+            return Err(Error::Usage(format!(
+                "block size {} is not a power of two in 16..=1024",
+                block_size
+            )));
+        }
+        let local: SocketAddr = if server.is_ipv4() {
+            ([0, 0, 0, 0], 0).into()
+        } else {
+            (std::net::Ipv6Addr::UNSPECIFIED, 0).into()
+        };
+        let socket = UdpSocket::bind(local).await.map_err(transport)?;
+        Ok(CoapClient {
+            socket,
+            server,
+            block_size,
+            message_id: AtomicU16::new(uuid::Uuid::new_v4().as_bytes()[0] as u16),
+        })
+    }
+
+    /// Requests a credential; returns the JWT.
+    pub async fn issue(&self, req: &IssueRequest) -> Result<(String, Exchange)> {
+        let exchange = self.post("issue", &serde_json::to_vec(req)?).await?;
+        if exchange.status != ResponseType::Content {
+            return Err(Error::Transport(format!(
+                "issue: {:?} {}",
+                exchange.status,
+                String::from_utf8_lossy(&exchange.payload)
+            )));
+        }
+        let jwt = String::from_utf8(exchange.payload.clone()).map_err(transport)?;
+        Ok((jwt, exchange))
+    }
+
+    /// Submits a JWT credential or presentation for verification.
+    pub async fn present(&self, jwt: &str) -> Result<(VerificationReport, Exchange)> {
+        let exchange = self.post("present", jwt.as_bytes()).await?;
+        if exchange.status != ResponseType::Content {
+            return Err(Error::Transport(format!(
+                "present: {:?} {}",
+                exchange.status,
+                String::from_utf8_lossy(&exchange.payload)
+            )));
+        }
+        let report = serde_json::from_slice(&exchange.payload)?;
+        Ok((report, exchange))
+    }
+
+    /// Confirmable POST to `path`, block-wise in both directions as needed.
+    pub async fn post(&self, path: &str, payload: &[u8]) -> Result<Exchange> {
+        let start = Instant::now();
+        let mut retransmissions = 0;
+        let block2 = |num: usize| BlockValue::new(num, false, self.block_size);
+
+        // Block1: send the request body, one block per confirmable request.
+        let chunks: Vec<&[u8]> = if payload.len() > self.block_size {
+            payload.chunks(self.block_size).collect()
+        } else {
+            vec![payload]
+        };
+        let blocks_sent = chunks.len();
+        let mut response = None;
+        for (num, chunk) in chunks.iter().enumerate() {
+            let mut packet = self.request(path);
+            if blocks_sent > 1 {
+                let more = num + 1 < blocks_sent;
+                let block1 = BlockValue::new(num, more, self.block_size).map_err(transport)?;
+                packet.add_option_as(CoapOption::Block1, block1);
+            }
+            if num + 1 == blocks_sent {
+                // Ask for the response in blocks of our size as well.
+                packet.add_option_as(CoapOption::Block2, block2(0).map_err(transport)?);
+            }
+            packet.payload = chunk.to_vec();
+            let (reply, retries) = self.exchange(&packet).await?;
+            retransmissions += retries;
+            if let MessageClass::Response(status) = reply.header.code {
+                if status != ResponseType::Continue && num + 1 < blocks_sent {
+                    return Ok(Exchange {
+                        status,
+                        payload: reply.payload,
+                        rtt: start.elapsed(),
+                        blocks_sent: num + 1,
+                        blocks_received: 1,
+                        retransmissions,
+                    });
+                }
+            }
+            response = Some(reply);
+        }
+        let mut reply = response.ok_or_else(|| transport("no response"))?;
+
+        // Block2: fetch the rest of the response.
+        let status = match reply.header.code {
+            MessageClass::Response(status) => status,
+            _ => return Err(transport("unexpected message class")),
+        };
+        let mut body = std::mem::take(&mut reply.payload);
+        let mut blocks_received = 1;
+        let mut more = reply
+            .get_first_option_as::<BlockValue>(CoapOption::Block2)
+            .and_then(|b| b.ok())
+            .is_some_and(|b| b.more);
+        while more {
+            let mut packet = self.request(path);
+            packet.add_option_as(
+                CoapOption::Block2,
+                block2(blocks_received).map_err(transport)?,
+            );
+            let (mut next, retries) = self.exchange(&packet).await?;
+            retransmissions += retries;
+            body.append(&mut next.payload);
+            blocks_received += 1;
+            more = next
+                .get_first_option_as::<BlockValue>(CoapOption::Block2)
+                .and_then(|b| b.ok())
+                .is_some_and(|b| b.more);
+        }
+
+        Ok(Exchange {
+            status,
+            payload: body,
+            rtt: start.elapsed(),
+            blocks_sent,
+            blocks_received,
+            retransmissions,
+        })
+    }
+
+    fn request(&self, path: &str) -> Packet {
+        let mut request: CoapRequest<SocketAddr> = CoapRequest::new();
+        request.set_method(RequestType::Post);
+        request.set_path(path);
+        let mut packet = request.message;
+        packet.header.set_version(1);
+        packet.header.set_type(MessageType::Confirmable);
+        packet.header.message_id = self.message_id.fetch_add(1, Ordering::Relaxed);
+        packet.set_token(uuid::Uuid::new_v4().as_bytes()[..4].to_vec());
+        packet
+    }
+
+    /// Sends a confirmable request and waits for its piggybacked response,
+    /// retransmitting with exponential back-off. Returns the retry count too.
+    async fn exchange(&self, packet: &Packet) -> Result<(Packet, u32)> {
+        let bytes = packet.to_bytes().map_err(transport)?;
+        let mut timeout = ACK_TIMEOUT;
+        let mut buf = [0u8; MAX_DATAGRAM];
+        for attempt in 0..=MAX_RETRANSMIT {
+            self.socket
+                .send_to(&bytes, self.server)
+                .await
+                .map_err(transport)?;
+            let deadline = tokio::time::Instant::now() + timeout;
+            loop {
+                let recv = tokio::time::timeout_at(deadline, self.socket.recv_from(&mut buf));
+                let (len, src) = match recv.await {
+                    Ok(received) => received.map_err(transport)?,
+                    Err(_) => break,
+                };
+                if src != self.server {
+                    continue;
+                }
+                let Ok(reply) = Packet::from_bytes(&buf[..len]) else {
+                    continue;
+                };
+                if reply.header.message_id == packet.header.message_id
+                    && reply.get_token() == packet.get_token()
+                {
+                    return Ok((reply, attempt));
+                }
+            }
+            timeout *= 2;
+        }
+        Err(Error::Transport(format!(
+            "no response from {} after {} retransmissions",
+            self.server, MAX_RETRANSMIT
+        )))
+    }
+}
@@ -0,0 +1,74 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use didkemb::cli::Args;
+use didkemb::coap::{self, CoapClient, CoapServer};
+use didkemb::issuer::{IssueRequest, Issuer};
+use didkemb::verifier::Verifier;
+use didkemb::DIDExampleStatic;
+
+// coap-bench [--server ADDR] [--block-size 256] [--runs 10]
+// Senza --server avvia un server locale sulla stessa macchina.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    didkemb::runtime::block_on(run())?
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::from_env();
+    let block_size = args.parse_or("block-size", coap::DEFAULT_BLOCK_SIZE)?;
+    let runs: u32 = args.parse_or("runs", 10)?;
+    let server: SocketAddr = match args.value("server") {
+        Some(addr) => addr.parse()?,
+        None => {
+            let issuer = Issuer::new(
+                didkemb::issuer_key()?,
+                didkemb::ISSUER_DID,
+                didkemb::VERIFICATION_METHOD,
+                Arc::new(DIDExampleStatic),
+            );
+            let verifier = Verifier::new(Arc::new(DIDExampleStatic));
+            let server = CoapServer::new(issuer, verifier, block_size + 64);
+            let (addr, server) = coap::serve(([127, 0, 0, 1], 0).into(), server).await?;
+            tokio::spawn(server);
+            addr
+        }
+    };
+    let client = CoapClient::connect(server, block_size).await?;
+
+    println!("claims,jwt_bytes,issue_ms,issue_blocks_up,issue_blocks_down,present_ms,present_blocks_up,present_blocks_down,retransmissions");
+    for claims in (10..=100).step_by(10) {
+        let req = IssueRequest {
+            subject: "urn:uuid:".to_string() + &uuid::Uuid::new_v4().to_string(),
+            claims: (1..=claims)
+                .map(|i| (format!("claim{}", i), format!("value {}", i).into()))
+                .collect(),
+            template: None,
+        };
+        let mut issue_ms = 0.0;
+        let mut present_ms = 0.0;
+        let mut last = None;
+        for _ in 0..runs {
+            let (jwt, issued) = client.issue(&req).await?;
+            let (report, presented) = client.present(&jwt).await?;
+            report.into_result()?;
+            issue_ms += issued.rtt.as_secs_f64() * 1000.0;
+            present_ms += presented.rtt.as_secs_f64() * 1000.0;
+            last = Some((jwt, issued, presented));
+        }
+        if let Some((jwt, issued, presented)) = last {
+            println!(
+                "{},{},{:.3},{},{},{:.3},{},{},{}",
+                claims,
+                jwt.len(),
+                issue_ms / runs as f64,
+                issued.blocks_sent,
+                issued.blocks_received,
+                present_ms / runs as f64,
+                presented.blocks_sent,
+                presented.blocks_received,
+                issued.retransmissions + presented.retransmissions,
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use didkemb::cli::Args;
+use didkemb::coap::{self, CoapServer};
+use didkemb::issuer::Issuer;
+use didkemb::template::CredentialTemplate;
+use didkemb::verifier::Verifier;
+use didkemb::DIDExampleStatic;
+
+// coap-server [--addr [::]:5683] [--max-message-size 1152] [--templates templates/]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    didkemb::runtime::block_on(run())?
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::from_env();
+    let addr: SocketAddr = args.parse_or("addr", "[::]:5683".parse()?)?;
+    let max_message_size = args.parse_or("max-message-size", 1152)?;
+
+    let mut issuer = Issuer::new(
+        didkemb::issuer_key()?,
+        didkemb::ISSUER_DID,
+        didkemb::VERIFICATION_METHOD,
+        Arc::new(DIDExampleStatic),
+    );
+    if let Some(dir) = args.value("templates") {
+        issuer = issuer.with_templates(CredentialTemplate::load_dir(dir)?);
+    }
+    let verifier = Verifier::new(Arc::new(DIDExampleStatic));
+
+    let server = CoapServer::new(issuer, verifier, max_message_size);
+    let (addr, server) = coap::serve(addr, server).await?;
+    eprintln!("coap-server listening on coap://{}", addr);
+    server.await?;
+    Ok(())
+}
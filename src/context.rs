@@ -0,0 +1,29 @@
+//! JSON-LD contexts shipped with the crate and the offline loader serving
+//! them. The IoT context gives device, sensor, reading, unit and timestamp
+//! their own IRIs, and maps any other claim (such as the benchmarks'
+//! `claimN`) into the IoT vocabulary rather than the credentials one.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+use ssi::jsonld::ContextLoader;
+
+pub const CREDENTIALS_V1: &str = "https://www.w3.org/2018/credentials/v1";
+pub const IOT_V1: &str = "https://didkemb.example/contexts/iot/v1";
+/// Namespace of the IoT terms.
+pub const IOT_VOCAB: &str = "https://didkemb.example/vocab/iot#";
+
+pub const IOT_V1_DOCUMENT: &str = include_str!("../contexts/iot-v1.jsonld");
+
+/// `@context` of an IoT credential.
+pub fn iot() -> Value {
+    serde_json::json!([CREDENTIALS_V1, IOT_V1])
+}
+
+/// The ssi static contexts plus the ones shipped here; nothing is fetched.
+pub fn loader() -> ContextLoader {
+    let documents = HashMap::from([(IOT_V1.to_string(), IOT_V1_DOCUMENT.to_string())]);
+    ContextLoader::default()
+        .with_context_map_from(documents)
+        .expect("shipped contexts are valid")
+}
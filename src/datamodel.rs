@@ -0,0 +1,242 @@
+//! VC Data Model 1.1 and 2.0. A 2.0 credential uses the
+//! `https://www.w3.org/ns/credentials/v2` context and `validFrom`/`validUntil`,
+//! and is secured as an `application/vc+jwt` envelope: the credential itself
+//! is the JWS payload, with `typ: vc+jwt`, instead of a `vc` claim.
+//!
+//! ssi's `Credential` only models 1.1, so 2.0 credentials are handled as JSON
+//! and mapped onto a `Credential` ([`from_v2`]) for the checks both share.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ssi::jwk::JWK;
+use ssi::vc::{Credential, LinkedDataProofOptions};
+use ssi_dids::did_resolve::DIDResolver;
+
+use crate::context::CREDENTIALS_V1;
+use crate::{Error, Result};
+
+pub const CREDENTIALS_V2: &str = "https://www.w3.org/ns/credentials/v2";
+/// JWS `typ` of a 2.0 credential envelope.
+pub const VC_JWT: &str = "vc+jwt";
+pub const MEDIA_TYPE: &str = "application/vc+jwt";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DataModel {
+    /// `vc` claim of a JWT, as in VC-JWT 1.1.
+    #[default]
+    #[serde(rename = "1.1")]
+    V1,
+    /// `application/vc+jwt` envelope.
+    #[serde(rename = "2.0")]
+    V2,
+}
+
+impl FromStr for DataModel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "1.1" | "v1" => Ok(DataModel::V1),
+            "2.0" | "v2" => Ok(DataModel::V2),
+            _ => Err("expected 1.1 or 2.0".to_string()),
+        }
+    }
+}
+
+impl fmt::Display for DataModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataModel::V1 => write!(f, "1.1"),
+            DataModel::V2 => write!(f, "2.0"),
+        }
+    }
+}
+
+impl DataModel {
+    /// The data model of `vc`, by its base context.
+    pub fn of(vc: &Credential) -> Self {
+        match vc.context.contains_uri(CREDENTIALS_V2) {
+            true => DataModel::V2,
+            false => DataModel::V1,
+        }
+    }
+
+    /// Rewrites a 1.1 credential for this data model.
+    pub fn convert(self, vc: Value) -> Value {
+        match self {
+            DataModel::V1 => vc,
+            DataModel::V2 => to_v2(vc),
+        }
+    }
+
+    /// Parses (1.1) or checks (2.0) `vc`, written for this data model, so it
+    /// can be signed any number of times.
+    pub fn prepare(self, vc: Value) -> Result<Unsigned> {
+        match self {
+            DataModel::V1 => Ok(Unsigned::V1(Box::new(serde_json::from_value(vc)?))),
+            DataModel::V2 => {
+                from_v2(&vc).map_err(Error::Encoding)?;
+                Ok(Unsigned::V2(vc))
+            }
+        }
+    }
+
+    /// Signs `vc`, written for this data model, as a JWT.
+    pub async fn issue(
+        self,
+        vc: &Value,
+        key: &JWK,
+        options: &LinkedDataProofOptions,
+        resolver: &dyn DIDResolver,
+    ) -> Result<String> {
+        self.prepare(vc.clone())?
+            .issue(key, options, resolver)
+            .await
+    }
+}
+
+/// A credential of [`DataModel::prepare`], ready for signing.
+#[derive(Debug, Clone)]
+pub enum Unsigned {
+    V1(Box<Credential>),
+    /// Already checked with [`from_v2`].
+    V2(Value),
+}
+
+impl Unsigned {
+    pub async fn issue(
+        &self,
+        key: &JWK,
+        options: &LinkedDataProofOptions,
+        resolver: &dyn DIDResolver,
+    ) -> Result<String> {
+        match self {
+            Unsigned::V1(vc) => crate::issue_jwt(vc, key, options, resolver).await,
+            Unsigned::V2(vc) => sign_vc_jwt(vc, key, options),
+        }
+    }
+}
+
+/// The 2.0 form of a 1.1 credential: the v1 context becomes v2,
+/// `issuanceDate` becomes `validFrom` and `expirationDate` `validUntil`.
+pub fn to_v2(mut vc: Value) -> Value {
+    if let Some(object) = vc.as_object_mut() {
+        let mut contexts = match object.remove("@context") {
+            Some(Value::Array(contexts)) => contexts,
+            Some(context) => vec![context],
+            None => Vec::new(),
+        };
+        contexts.retain(|c| c != CREDENTIALS_V1 && c != CREDENTIALS_V2);
+        contexts.insert(0, CREDENTIALS_V2.into());
+        object.insert("@context".to_string(), contexts.into());
+        for (v1, v2) in [
+            ("issuanceDate", "validFrom"),
+            ("expirationDate", "validUntil"),
+        ] {
+            if let Some(date) = object.remove(v1) {
+                object.insert(v2.to_string(), date);
+            }
+        }
+    }
+    vc
+}
+
+/// `vc` as a `Credential`, for the checks shared with 1.1: `validFrom` and
+/// `validUntil` fill `issuanceDate` and `expirationDate`.
+pub fn from_v2(vc: &Value) -> Result<Credential, String> {
+    if vc["@context"][0] != CREDENTIALS_V2 {
+        return Err(format!("First @context must be {}", CREDENTIALS_V2));
+    }
+    let mut view = vc.clone();
+    for (v2, v1) in [
+        ("validFrom", "issuanceDate"),
+        ("validUntil", "expirationDate"),
+    ] {
+        if let Some(date) = view.as_object_mut().and_then(|o| o.remove(v2)) {
+            view[v1] = date;
+        }
+    }
+    let view: Credential = serde_json::from_value(view).map_err(|e| e.to_string())?;
+    if !(&view.type_)
+        .into_iter()
+        .any(|t| t == "VerifiableCredential")
+    {
+        return Err("Missing type VerifiableCredential".to_string());
+    }
+    if view.issuer.is_none() {
+        return Err("Missing issuer".to_string());
+    }
+    if view.credential_subject.is_empty()
+        || (&view.credential_subject).into_iter().any(|s| s.is_empty())
+    {
+        return Err("Empty credentialSubject".to_string());
+    }
+    Ok(view)
+}
+
+/// Whether `jwt` is an `application/vc+jwt` envelope.
+pub fn is_envelope(jwt: &str) -> bool {
+    ssi::jws::decode_unverified(jwt)
+        .ok()
+        .and_then(|(header, _)| header.type_)
+        .is_some_and(|typ| typ == VC_JWT)
+}
+
+/// Secures a 2.0 credential as `application/vc+jwt`, with the key id taken
+/// from `options.verification_method`.
+pub fn issue_vc_jwt(vc: &Value, key: &JWK, options: &LinkedDataProofOptions) -> Result<String> {
+    from_v2(vc).map_err(Error::Encoding)?;
+    sign_vc_jwt(vc, key, options)
+}
+
+fn sign_vc_jwt(vc: &Value, key: &JWK, options: &LinkedDataProofOptions) -> Result<String> {
+    let kid = options
+        .verification_method
+        .as_ref()
+        .ok_or_else(|| Error::Signing("vc+jwt needs a verification method".to_string()))?;
+    let header = ssi::jws::Header {
+        algorithm: key
+            .get_algorithm()
+            .ok_or_else(|| Error::Key("Key has no algorithm".to_string()))?,
+        key_id: Some(kid.to_string()),
+        type_: Some(VC_JWT.to_string()),
+        content_type: Some("vc".to_string()),
+        ..Default::default()
+    };
+    ssi::jws::encode_sign_custom_header(&serde_json::to_string(vc)?, key, &header)
+        .map_err(|e| Error::Signing(e.to_string()))
+}
+
+/// Credential of a JWT in either form, without checking the signature.
+pub fn decode_unverified(jwt: &str) -> Result<Value, String> {
+    let (header, payload) = ssi::jws::decode_unverified(jwt).map_err(|e| e.to_string())?;
+    match header.type_.as_deref() {
+        Some(VC_JWT) => serde_json::from_slice(&payload).map_err(|e| e.to_string()),
+        _ => Credential::from_jwt_unsigned(jwt)
+            .map_err(|e| e.to_string())
+            .and_then(|vc| serde_json::to_value(vc).map_err(|e| e.to_string())),
+    }
+}
+
+/// A `Credential` view of a JWT in either form, without checking the
+/// signature.
+pub fn credential_unverified(jwt: &str) -> Result<Credential, String> {
+    match is_envelope(jwt) {
+        true => from_v2(&decode_unverified(jwt)?),
+        false => Credential::from_jwt_unsigned(jwt).map_err(|e| e.to_string()),
+    }
+}
+
+/// Decodes a JWT credential in either form, checking its signature against
+/// `key` only.
+pub fn decode_jwt(jwt: &str, key: &JWK) -> Result<Value> {
+    let (header, payload) =
+        ssi::jws::decode_verify(jwt, key).map_err(|e| Error::Encoding(e.to_string()))?;
+    match header.type_.as_deref() {
+        Some(VC_JWT) => Ok(serde_json::from_slice(&payload)?),
+        _ => Ok(serde_json::to_value(crate::decode_jwt(jwt, key)?)?),
+    }
+}
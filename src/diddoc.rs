@@ -0,0 +1,91 @@
+use didkemb::cli::Args;
+use didkemb::document::{DocumentBuilder, KeyType, Relationship};
+use didkemb::resolver::DocumentHistory;
+use didkemb::Error;
+
+// did-doc (--did DID [--controller DID] | --doc FILE) [--remove ID]
+//         [--key chiave.json [--id key-N] [--type Ed25519VerificationKey2020|JsonWebKey2020|Multikey]
+//                            [--purpose authentication,assertionMethod,keyAgreement]
+//                            [--rotate ID]]
+//         [--deactivate] [--out FILE] [--history FILE]
+// Crea o modifica un documento DID; con --rotate la nuova chiave sostituisce ID
+// mantenendone tipo e relazioni. Con --deactivate il DID viene disattivato (es.
+// dispositivo compromesso) e i verificatori ne rifiutano le credenziali. Il
+// risultato va su stdout o in --out; con --history viene anche aggiunto come
+// nuova versione allo storico (con --deactivate, come versione finale che
+// disattiva il DID).
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::from_env();
+    let mut builder = match (args.value("doc"), args.value("did")) {
+        (Some(path), _) => DocumentBuilder::from_json(
+            &std::fs::read_to_string(path).map_err(|e| Error::Usage(format!("{}: {}", path, e)))?,
+        )?,
+        (None, Some(did)) => DocumentBuilder::new(did),
+        (None, None) => return Err(Error::Usage("--did or --doc is required".to_string()).into()),
+    };
+    if let Some(controller) = args.value("controller") {
+        builder = builder.controller(controller);
+    }
+
+    if let Some(id) = args.value("remove") {
+        if !builder.remove(id) {
+            return Err(Error::Usage(format!("No verification method {}", id)).into());
+        }
+    }
+
+    if let Some(path) = args.value("key") {
+        let key = didkemb::load_key(path)?;
+        let fragment = match args.value("id") {
+            Some(id) => id.to_string(),
+            None => (1..)
+                .map(|n| format!("key-{}", n))
+                .find(|f| builder.methods().all(|m| !m.ends_with(&format!("#{}", f))))
+                .unwrap_or_default(),
+        };
+        let id = match args.value("rotate") {
+            Some(old) => builder.rotate(old, &fragment, &key)?,
+            None => {
+                let key_type: KeyType = args.parse_or("type", KeyType::Multikey)?;
+                let purposes = match args.value("purpose") {
+                    Some(list) => list
+                        .split(',')
+                        .map(str::parse)
+                        .collect::<Result<Vec<Relationship>, _>>()?,
+                    None => vec![Relationship::Authentication, Relationship::AssertionMethod],
+                };
+                builder.add_key(&fragment, &key, key_type, &purposes)?
+            }
+        };
+        let relationships: Vec<String> = builder
+            .relationships_of(&id)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        eprintln!("{}: {}", id, relationships.join(", "));
+    }
+
+    if args.flag("deactivate") {
+        builder.deactivate();
+        eprintln!("{} deactivated", builder.id());
+    }
+
+    builder.document()?;
+    if let Some(path) = args.value("history") {
+        let mut history = match std::path::Path::new(path).exists() {
+            true => DocumentHistory::load(path)?,
+            false => DocumentHistory::new(builder.id()),
+        };
+        let version = match builder.is_deactivated() {
+            true => history.deactivate(builder.build(), chrono::Utc::now())?,
+            false => history.push(builder.build(), chrono::Utc::now())?,
+        };
+        eprintln!("{} version {}", builder.id(), version.version_id);
+        history.save(path)?;
+    }
+    let json = serde_json::to_string_pretty(&builder.to_file())?;
+    match args.value("out") {
+        Some(path) => std::fs::write(path, json + "\n")?,
+        None => println!("{}", json),
+    }
+    Ok(())
+}
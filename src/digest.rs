@@ -0,0 +1,221 @@
+//! Digests of the data a credential attests to. The `hash` claim carries the
+//! digest of a file, stream or sensor record, next to `hashAlgorithm` and
+//! `contentLength`, so a verifier holding the payload can check it.
+
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::Digest;
+
+use crate::{Error, Result};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HashAlgorithm {
+    #[default]
+    #[serde(rename = "sha-256")]
+    Sha256,
+    #[serde(rename = "sha3-256")]
+    Sha3_256,
+    #[serde(rename = "blake3")]
+    Blake3,
+}
+
+impl HashAlgorithm {
+    pub const ALL: [HashAlgorithm; 3] = [
+        HashAlgorithm::Sha256,
+        HashAlgorithm::Sha3_256,
+        HashAlgorithm::Blake3,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha-256",
+            HashAlgorithm::Sha3_256 => "sha3-256",
+            HashAlgorithm::Blake3 => "blake3",
+        }
+    }
+
+    /// Digest of the concatenation of `parts`.
+    pub fn digest(&self, parts: &[&[u8]]) -> Vec<u8> {
+        let mut hasher = self.hasher();
+        for part in parts {
+            hasher.update(part);
+        }
+        hasher.finalize()
+    }
+
+    fn hasher(&self) -> Hasher {
+        match self {
+            HashAlgorithm::Sha256 => Hasher::Sha256(sha2::Sha256::new()),
+            HashAlgorithm::Sha3_256 => Hasher::Sha3_256(Box::default()),
+            HashAlgorithm::Blake3 => Hasher::Blake3(Box::default()),
+        }
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for HashAlgorithm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        HashAlgorithm::ALL
+            .into_iter()
+            .find(|a| a.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| Error::Payload(format!("Unknown hash algorithm {}", s)))
+    }
+}
+
+enum Hasher {
+    Sha256(sha2::Sha256),
+    Sha3_256(Box<sha3::Sha3_256>),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(h) => h.update(data),
+            Hasher::Sha3_256(h) => h.update(data),
+            Hasher::Blake3(h) => {
+                h.update(data);
+            }
+        }
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        match self {
+            Hasher::Sha256(h) => h.finalize().to_vec(),
+            Hasher::Sha3_256(h) => h.finalize().to_vec(),
+            Hasher::Blake3(h) => h.finalize().as_bytes().to_vec(),
+        }
+    }
+}
+
+/// `0x`-prefixed lowercase hex, the format of the benchmark `hash` claim.
+pub fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(2 + bytes.len() * 2);
+    hex.push_str("0x");
+    for b in bytes {
+        hex.push_str(&format!("{:02x}", b));
+    }
+    hex
+}
+
+/// Inverse of [`to_hex`]; the `0x` prefix is optional.
+pub fn from_hex(hex: &str) -> Result<Vec<u8>> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if !hex.is_ascii() || !hex.len().is_multiple_of(2) {
+        return Err(Error::Encoding(format!("Invalid hex string {}", hex)));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|e| Error::Encoding(format!("{}: {}", hex, e)))
+        })
+        .collect()
+}
+
+/// The claims describing an attested payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayloadDigest {
+    pub hash: String,
+    pub hash_algorithm: HashAlgorithm,
+    pub content_length: u64,
+}
+
+impl PayloadDigest {
+    pub fn of_bytes(algorithm: HashAlgorithm, bytes: &[u8]) -> Self {
+        PayloadDigest {
+            hash: to_hex(&algorithm.digest(&[bytes])),
+            hash_algorithm: algorithm,
+            content_length: bytes.len() as u64,
+        }
+    }
+
+    /// Hashes everything `reader` yields, without holding it in memory.
+    pub fn of_reader(algorithm: HashAlgorithm, mut reader: impl Read) -> Result<Self> {
+        let mut hasher = algorithm.hasher();
+        let mut buf = [0u8; 8192];
+        let mut content_length = 0;
+        loop {
+            let n = reader
+                .read(&mut buf)
+                .map_err(|e| Error::Payload(e.to_string()))?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            content_length += n as u64;
+        }
+        Ok(PayloadDigest {
+            hash: to_hex(&hasher.finalize()),
+            hash_algorithm: algorithm,
+            content_length,
+        })
+    }
+
+    pub fn of_file(algorithm: HashAlgorithm, path: impl AsRef<Path>) -> Result<Self> {
+        let file = std::fs::File::open(path.as_ref())
+            .map_err(|e| Error::Payload(format!("{}: {}", path.as_ref().display(), e)))?;
+        Self::of_reader(algorithm, std::io::BufReader::new(file))
+    }
+
+    /// Digest of the compact JSON serialization of `record`.
+    pub fn of_record(algorithm: HashAlgorithm, record: &impl Serialize) -> Result<Self> {
+        Ok(Self::of_bytes(algorithm, &serde_json::to_vec(record)?))
+    }
+
+    /// `hash`, `hashAlgorithm` and `contentLength`, ready to merge into a
+    /// `credentialSubject`.
+    pub fn claims(&self) -> Map<String, Value> {
+        match serde_json::to_value(self) {
+            Ok(Value::Object(claims)) => claims,
+            _ => unreachable!("PayloadDigest serializes to an object"),
+        }
+    }
+
+    /// Reads the digest claims back out of a `credentialSubject`.
+    pub fn from_subject(subject: &Value) -> Result<Self> {
+        let field = |name: &str| {
+            subject
+                .get(name)
+                .cloned()
+                .ok_or_else(|| Error::Payload(format!("credentialSubject has no {}", name)))
+        };
+        Ok(PayloadDigest {
+            hash: serde_json::from_value(field("hash")?)?,
+            hash_algorithm: serde_json::from_value(field("hashAlgorithm")?)?,
+            content_length: serde_json::from_value(field("contentLength")?)?,
+        })
+    }
+
+    /// Hashes `payload` with the same algorithm and compares; the error says
+    /// what differs.
+    pub fn check(&self, payload: impl Read) -> Result<(), String> {
+        let actual = Self::of_reader(self.hash_algorithm, payload).map_err(|e| e.to_string())?;
+        if actual.content_length != self.content_length {
+            return Err(format!(
+                "Payload is {} bytes, credential attests {}",
+                actual.content_length, self.content_length
+            ));
+        }
+        if !actual.hash.eq_ignore_ascii_case(&self.hash) {
+            return Err(format!(
+                "Payload {} digest {} does not match attested {}",
+                self.hash_algorithm, actual.hash, self.hash
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,390 @@
+//! DID document authoring, for documents served by [`FileResolver`] or
+//! did:web: verification methods are added, removed and rotated by fragment,
+//! and the contexts they need are filled in on [`build`](DocumentBuilder::build).
+//!
+//! [`FileResolver`]: crate::resolver::FileResolver
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::{json, Map, Value};
+use ssi::jwk::{Params, JWK};
+use ssi_dids::Document;
+
+use crate::resolver::jwk::JWS_2020_CONTEXT;
+use crate::resolver::peer::encode_key;
+use crate::{Error, Result};
+
+pub const DID_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyType {
+    /// Ed25519 only, as `publicKeyMultibase`.
+    Ed25519VerificationKey2020,
+    /// Any key, as `publicKeyJwk`.
+    JsonWebKey2020,
+    /// Ed25519 or X25519, as `publicKeyMultibase`.
+    Multikey,
+}
+
+impl KeyType {
+    pub const ALL: [KeyType; 3] = [
+        KeyType::Ed25519VerificationKey2020,
+        KeyType::JsonWebKey2020,
+        KeyType::Multikey,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyType::Ed25519VerificationKey2020 => "Ed25519VerificationKey2020",
+            KeyType::JsonWebKey2020 => "JsonWebKey2020",
+            KeyType::Multikey => "Multikey",
+        }
+    }
+
+    /// Context defining the type and its key property.
+    pub fn context(self) -> &'static str {
+        match self {
+            KeyType::Ed25519VerificationKey2020 => {
+                "https://w3id.org/security/suites/ed25519-2020/v1"
+            }
+            KeyType::JsonWebKey2020 => JWS_2020_CONTEXT,
+            KeyType::Multikey => "https://w3id.org/security/multikey/v1",
+        }
+    }
+
+    /// The key property and value for `jwk`'s public part.
+    fn material(self, jwk: &JWK) -> Option<(&'static str, Value)> {
+        match self {
+            KeyType::Ed25519VerificationKey2020 if curve(jwk) != Some("Ed25519") => None,
+            KeyType::Ed25519VerificationKey2020 | KeyType::Multikey => {
+                Some(("publicKeyMultibase", encode_key(jwk)?.into()))
+            }
+            KeyType::JsonWebKey2020 => Some(("publicKeyJwk", json!(jwk.to_public()))),
+        }
+    }
+}
+
+impl fmt::Display for KeyType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for KeyType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        KeyType::ALL
+            .into_iter()
+            .find(|t| t.name() == s)
+            .ok_or_else(|| Error::Usage(format!("Unknown verification method type {}", s)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Relationship {
+    Authentication,
+    AssertionMethod,
+    KeyAgreement,
+}
+
+impl Relationship {
+    pub const ALL: [Relationship; 3] = [
+        Relationship::Authentication,
+        Relationship::AssertionMethod,
+        Relationship::KeyAgreement,
+    ];
+
+    /// The document property.
+    pub fn name(self) -> &'static str {
+        match self {
+            Relationship::Authentication => "authentication",
+            Relationship::AssertionMethod => "assertionMethod",
+            Relationship::KeyAgreement => "keyAgreement",
+        }
+    }
+}
+
+impl fmt::Display for Relationship {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Relationship {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Relationship::ALL
+            .into_iter()
+            .find(|r| r.name() == s)
+            .ok_or_else(|| Error::Usage(format!("Unknown verification relationship {}", s)))
+    }
+}
+
+fn curve(jwk: &JWK) -> Option<&str> {
+    match jwk.params {
+        Params::OKP(ref params) => Some(params.curve.as_str()),
+        Params::EC(ref params) => params.curve.as_deref(),
+        _ => None,
+    }
+}
+
+/// Id of a verification method reference, embedded or not.
+fn reference_id(reference: &Value) -> Option<&str> {
+    match reference {
+        Value::String(id) => Some(id),
+        Value::Object(vm) => vm.get("id").and_then(Value::as_str),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DocumentBuilder {
+    id: String,
+    controller: Option<String>,
+    contexts: Vec<Value>,
+    methods: Vec<Map<String, Value>>,
+    relationships: BTreeMap<Relationship, Vec<Value>>,
+    /// Properties the builder does not manage, such as `service`.
+    other: Map<String, Value>,
+    deactivated: bool,
+}
+
+impl DocumentBuilder {
+    /// An empty document for `did`.
+    pub fn new(did: impl Into<String>) -> Self {
+        DocumentBuilder {
+            id: did.into(),
+            controller: None,
+            contexts: vec![DID_CONTEXT.into()],
+            methods: Vec::new(),
+            relationships: BTreeMap::new(),
+            other: Map::new(),
+            deactivated: false,
+        }
+    }
+
+    /// Sets the document `controller`, when it is not the DID itself.
+    pub fn controller(mut self, controller: impl Into<String>) -> Self {
+        self.controller = Some(controller.into());
+        self
+    }
+
+    /// Starts from an existing document, keeping whatever it already has. A
+    /// [`to_file`](Self::to_file) resolution result is accepted too.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let mut doc: Map<String, Value> = serde_json::from_str(json)?;
+        let mut deactivated = false;
+        if let Some(document) = doc.remove("didDocument") {
+            deactivated = doc
+                .get("didDocumentMetadata")
+                .and_then(|meta| meta.get("deactivated"))
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            doc = serde_json::from_value(document)?;
+        }
+        let id = match doc.remove("id") {
+            Some(Value::String(id)) => id,
+            _ => return Err(Error::Encoding("DID document without id".to_string())),
+        };
+        let mut builder = DocumentBuilder::new(id);
+        builder.controller = match doc.remove("controller") {
+            Some(Value::String(controller)) => Some(controller),
+            Some(_) => return Err(Error::Encoding("Unsupported controller".to_string())),
+            None => None,
+        };
+        builder.contexts = match doc.remove("@context") {
+            Some(Value::Array(contexts)) => contexts,
+            Some(context) => vec![context],
+            None => vec![DID_CONTEXT.into()],
+        };
+        if let Some(methods) = doc.remove("verificationMethod") {
+            builder.methods = serde_json::from_value(methods)?;
+        }
+        for relationship in Relationship::ALL {
+            if let Some(references) = doc.remove(relationship.name()) {
+                builder
+                    .relationships
+                    .insert(relationship, serde_json::from_value(references)?);
+            }
+        }
+        builder.other = doc;
+        builder.deactivated = deactivated;
+        Ok(builder)
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Absolute id of a verification method given by fragment or full id.
+    fn method_id(&self, fragment: &str) -> String {
+        match fragment.strip_prefix('#') {
+            Some(fragment) => format!("{}#{}", self.id, fragment),
+            None if fragment.contains('#') => fragment.to_string(),
+            None => format!("{}#{}", self.id, fragment),
+        }
+    }
+
+    /// Ids of the verification methods, in order.
+    pub fn methods(&self) -> impl Iterator<Item = &str> {
+        self.methods
+            .iter()
+            .filter_map(|vm| vm.get("id").and_then(Value::as_str))
+    }
+
+    /// Relationships `id` (fragment or full id) is listed under.
+    pub fn relationships_of(&self, id: &str) -> Vec<Relationship> {
+        let id = self.method_id(id);
+        self.relationships
+            .iter()
+            .filter(|(_, refs)| refs.iter().any(|r| reference_id(r) == Some(&id)))
+            .map(|(relationship, _)| *relationship)
+            .collect()
+    }
+
+    /// Adds the public part of `jwk` as `#fragment` and lists it under each of
+    /// `relationships`. X25519 keys are only for key agreement, and signing
+    /// keys never are.
+    pub fn add_key(
+        &mut self,
+        fragment: &str,
+        jwk: &JWK,
+        key_type: KeyType,
+        relationships: &[Relationship],
+    ) -> Result<String> {
+        let id = self.method_id(fragment);
+        if self.methods().any(|m| m == id) {
+            return Err(Error::Key(format!("{} already exists", id)));
+        }
+        let (property, material) = key_type
+            .material(jwk)
+            .ok_or_else(|| Error::Key(format!("Unsupported key for {}", key_type)))?;
+        let agreement_key = curve(jwk) == Some("X25519");
+        for relationship in relationships {
+            if agreement_key != (*relationship == Relationship::KeyAgreement) {
+                return Err(Error::Key(format!(
+                    "{} key cannot be used for {}",
+                    curve(jwk).unwrap_or("this"),
+                    relationship
+                )));
+            }
+        }
+        let mut vm = Map::new();
+        vm.insert("id".to_string(), id.clone().into());
+        vm.insert("type".to_string(), key_type.name().into());
+        vm.insert(
+            "controller".to_string(),
+            self.controller
+                .clone()
+                .unwrap_or_else(|| self.id.clone())
+                .into(),
+        );
+        vm.insert(property.to_string(), material);
+        self.methods.push(vm);
+        for relationship in relationships {
+            self.relationships
+                .entry(*relationship)
+                .or_default()
+                .push(id.clone().into());
+        }
+        Ok(id)
+    }
+
+    /// Removes a verification method and every reference to it; `false` if
+    /// there was none.
+    pub fn remove(&mut self, fragment: &str) -> bool {
+        let id = self.method_id(fragment);
+        let before = self.methods.len();
+        self.methods
+            .retain(|vm| vm.get("id").and_then(Value::as_str) != Some(&id));
+        let mut removed = self.methods.len() != before;
+        for references in self.relationships.values_mut() {
+            let before = references.len();
+            references.retain(|r| reference_id(r) != Some(&id));
+            removed |= references.len() != before;
+        }
+        self.relationships
+            .retain(|_, references| !references.is_empty());
+        removed
+    }
+
+    /// Replaces the key of `old` with `jwk` under `new_fragment`, in the same
+    /// relationships and with the same type.
+    pub fn rotate(&mut self, old: &str, new_fragment: &str, jwk: &JWK) -> Result<String> {
+        let old_id = self.method_id(old);
+        let key_type: KeyType = self
+            .methods
+            .iter()
+            .find(|vm| vm.get("id").and_then(Value::as_str) == Some(&old_id))
+            .and_then(|vm| vm.get("type").and_then(Value::as_str))
+            .ok_or_else(|| Error::Key(format!("No verification method {}", old_id)))?
+            .parse()?;
+        let relationships = self.relationships_of(&old_id);
+        self.remove(&old_id);
+        self.add_key(new_fragment, jwk, key_type, &relationships)
+    }
+
+    /// Marks the DID as deactivated, for instance when the device holding its
+    /// keys is compromised. Verifiers then refuse its credentials.
+    pub fn deactivate(&mut self) {
+        self.deactivated = true;
+    }
+
+    pub fn is_deactivated(&self) -> bool {
+        self.deactivated
+    }
+
+    /// The document JSON, with a context for each verification method type.
+    pub fn build(&self) -> Value {
+        let mut contexts = self.contexts.clone();
+        for vm in &self.methods {
+            let context = vm
+                .get("type")
+                .and_then(Value::as_str)
+                .and_then(|t| t.parse::<KeyType>().ok())
+                .map(|t| Value::from(t.context()));
+            if let Some(context) = context {
+                if !contexts.contains(&context) {
+                    contexts.push(context);
+                }
+            }
+        }
+        let mut doc = Map::new();
+        doc.insert("@context".to_string(), contexts.into());
+        doc.insert("id".to_string(), self.id.clone().into());
+        if let Some(ref controller) = self.controller {
+            doc.insert("controller".to_string(), controller.clone().into());
+        }
+        doc.insert(
+            "verificationMethod".to_string(),
+            self.methods.iter().cloned().map(Value::Object).collect(),
+        );
+        for (relationship, references) in &self.relationships {
+            doc.insert(relationship.name().to_string(), references.clone().into());
+        }
+        doc.extend(self.other.clone());
+        Value::Object(doc)
+    }
+
+    /// What to store for [`FileResolver`](crate::resolver::FileResolver): the
+    /// document, or once deactivated a resolution result carrying
+    /// `deactivated: true` in its metadata.
+    pub fn to_file(&self) -> Value {
+        match self.deactivated {
+            true => json!({
+                "didDocument": self.build(),
+                "didDocumentMetadata": { "deactivated": true },
+            }),
+            false => self.build(),
+        }
+    }
+
+    /// The built document, checked to parse as a DID document.
+    pub fn document(&self) -> Result<Document> {
+        serde_json::from_value(self.build()).map_err(|e| Error::Encoding(e.to_string()))
+    }
+}
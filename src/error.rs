@@ -0,0 +1,59 @@
+use thiserror::Error;
+
+use crate::report::VerificationReport;
+
+/// Errors returned by the issuance and verification helpers.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The key could not be parsed or generated.
+    #[error("Invalid key: {0}")]
+    Key(String),
+    /// JSON, JWT or credential (de)serialization failed.
+    #[error("Encoding error: {0}")]
+    Encoding(String),
+    /// Unknown credential template, or one that renders to an invalid credential.
+    #[error("Template error: {0}")]
+    Template(String),
+    /// A credential subject does not match its `credentialSchema`.
+    #[error("Schema validation failed: {}", crate::schema::describe(.0))]
+    Schema(Vec<crate::schema::SchemaError>),
+    #[error("Unable to sign credential: {0}")]
+    Signing(String),
+    #[error("Unable to resolve {did}: {reason}")]
+    Resolution { did: String, reason: String },
+    /// Verification ran but at least one check failed.
+    #[error("Verification failed: {0}")]
+    Verification(Box<VerificationReport>),
+    /// The attested payload could not be read, or its digest claims are malformed.
+    #[error("Payload error: {0}")]
+    Payload(String),
+    /// Bad command-line arguments.
+    #[error("Usage: {0}")]
+    Usage(String),
+    /// A network listener or client failed.
+    #[error("Transport error: {0}")]
+    Transport(String),
+    /// CPU or memory usage of the process could not be read.
+    #[error("Unable to measure process: {0}")]
+    Measurement(String),
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Encoding(e.to_string())
+    }
+}
+
+impl From<ssi::jwk::Error> for Error {
+    fn from(e: ssi::jwk::Error) -> Self {
+        Error::Key(e.to_string())
+    }
+}
+
+impl From<psutil::process::ProcessError> for Error {
+    fn from(e: psutil::process::ProcessError) -> Self {
+        Error::Measurement(e.to_string())
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -0,0 +1,204 @@
+//! Negative-path cases for [`report::verify_jwt`]: every JWT produced here
+//! must be rejected by the verifier, at the check expected for its tampering.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ssi::jwk::{Algorithm, JWK};
+use ssi::jws::Header;
+use ssi::vc::{Credential, StringOrURI, URI};
+use ssi_dids::did_resolve::DIDResolver;
+
+use crate::report::{self, CheckKind, VerificationReport};
+use crate::{DIDExampleStatic, Error, Result, ISSUER_DID, VERIFICATION_METHOD};
+
+/// DID that `DIDExampleStatic` does not know about.
+pub const UNRESOLVABLE_DID: &str = "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tamper {
+    /// A byte of the decoded payload is changed, the signature is kept.
+    FlippedPayload,
+    /// The signature of a different credential is attached.
+    SwappedSignature,
+    /// Signed by a key that is not in the issuer's DID document.
+    WrongKey,
+    /// `kid` names a verification method the issuer does not have.
+    UnknownKid,
+    /// `alg: none` with an empty signature.
+    AlgNone,
+    /// Issuer DID cannot be resolved by `DIDExampleStatic`.
+    UnresolvableIssuer,
+    /// Token cut in half.
+    Truncated,
+}
+
+impl Tamper {
+    pub const ALL: [Tamper; 7] = [
+        Tamper::FlippedPayload,
+        Tamper::SwappedSignature,
+        Tamper::WrongKey,
+        Tamper::UnknownKid,
+        Tamper::AlgNone,
+        Tamper::UnresolvableIssuer,
+        Tamper::Truncated,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tamper::FlippedPayload => "flipped-payload",
+            Tamper::SwappedSignature => "swapped-signature",
+            Tamper::WrongKey => "wrong-key",
+            Tamper::UnknownKid => "unknown-kid",
+            Tamper::AlgNone => "alg-none",
+            Tamper::UnresolvableIssuer => "unresolvable-issuer",
+            Tamper::Truncated => "truncated",
+        }
+    }
+
+    /// The check [`report::verify_jwt`] is expected to fail.
+    pub fn expected_failure(&self) -> CheckKind {
+        match self {
+            Tamper::FlippedPayload
+            | Tamper::SwappedSignature
+            | Tamper::WrongKey
+            | Tamper::AlgNone => CheckKind::Signature,
+            Tamper::UnknownKid => CheckKind::VerificationRelationship,
+            Tamper::UnresolvableIssuer => CheckKind::IssuerResolution,
+            Tamper::Truncated => CheckKind::Format,
+        }
+    }
+
+    /// Builds a tampered JWT for `vc`, signed (where applicable) with `key`.
+    pub async fn apply(&self, vc: &Credential, key: &JWK) -> Result<String> {
+        Ok(match self {
+            Tamper::FlippedPayload => {
+                let jwt = sign(vc, key).await?;
+                let (_, payload) = ssi::jws::decode_unverified(&jwt).map_err(encoding)?;
+                // Flip a bit inside the first claim value so the payload stays valid JSON.
+                let at = find(&payload, b"value 1")
+                    .ok_or_else(|| Error::Encoding("No claim to flip".to_string()))?;
+                flip_payload_byte(&jwt, at)
+                    .ok_or_else(|| Error::Encoding("Unable to re-encode payload".to_string()))?
+            }
+            Tamper::SwappedSignature => {
+                let jwt = sign(vc, key).await?;
+                let mut other = vc.clone();
+                other.id = Some(StringOrURI::String(
+                    "urn:uuid:".to_string() + &uuid::Uuid::new_v4().to_string(),
+                ));
+                let other_jwt = sign(&other, key).await?;
+                let (header, payload, _) = ssi::jws::split_jws(&jwt).map_err(encoding)?;
+                let (_, _, signature) = ssi::jws::split_jws(&other_jwt).map_err(encoding)?;
+                [header, payload, signature].join(".")
+            }
+            Tamper::WrongKey => {
+                let other_key = JWK::generate_ed25519()?;
+                sign_with_header(
+                    vc,
+                    &other_key,
+                    header(Algorithm::EdDSA, VERIFICATION_METHOD),
+                )?
+            }
+            Tamper::UnknownKid => {
+                let kid = ISSUER_DID.to_string() + "#unknown";
+                sign_with_header(vc, key, header(Algorithm::EdDSA, &kid))?
+            }
+            Tamper::AlgNone => {
+                let header = header(Algorithm::None, VERIFICATION_METHOD);
+                let claims = serde_json::to_vec(&vc.to_jwt_claims().map_err(encoding)?)?;
+                let header = serde_json::to_vec(&header)?;
+                URL_SAFE_NO_PAD.encode(header) + "." + &URL_SAFE_NO_PAD.encode(claims) + "."
+            }
+            Tamper::UnresolvableIssuer => {
+                let mut vc = vc.clone();
+                vc.issuer = Some(ssi::vc::Issuer::URI(URI::String(
+                    UNRESOLVABLE_DID.to_string(),
+                )));
+                sign_with_header(&vc, key, header(Algorithm::EdDSA, VERIFICATION_METHOD))?
+            }
+            Tamper::Truncated => {
+                let jwt = sign(vc, key).await?;
+                truncate(&jwt, jwt.len() / 2)
+            }
+        })
+    }
+
+    /// Checks that verification of the tampered JWT fails at the expected
+    /// check.
+    pub async fn check(&self, vc: &Credential, key: &JWK) -> Result<(), String> {
+        let jwt = self.apply(vc, key).await.map_err(|e| e.to_string())?;
+        let report = verify(&jwt).await;
+        match report.failure() {
+            None => Err(format!("{}: tampered credential verified", self.name())),
+            Some(failure) if failure.check != self.expected_failure() => Err(format!(
+                "{}: expected {:?} to fail, got {}",
+                self.name(),
+                self.expected_failure(),
+                report
+            )),
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+/// Signs `vc` the same way the benchmark binaries do.
+pub async fn sign(vc: &Credential, key: &JWK) -> Result<String> {
+    crate::issue_jwt(vc, key, &crate::proof_options(), &DIDExampleStatic).await
+}
+
+/// Verifies `jwt` against `DIDExampleStatic`.
+pub async fn verify(jwt: &str) -> VerificationReport {
+    verify_with(jwt, &DIDExampleStatic).await
+}
+
+pub async fn verify_with(jwt: &str, resolver: &dyn DIDResolver) -> VerificationReport {
+    report::verify_jwt(jwt, None, resolver, &mut crate::context::loader()).await
+}
+
+/// Re-encodes `jwt` with bit 0 of decoded payload byte `index` flipped.
+pub fn flip_payload_byte(jwt: &str, index: usize) -> Option<String> {
+    let (header, payload, signature) = ssi::jws::split_jws(jwt).ok()?;
+    let mut payload = URL_SAFE_NO_PAD.decode(payload).ok()?;
+    *payload.get_mut(index)? ^= 0x01;
+    Some([header, &URL_SAFE_NO_PAD.encode(payload), signature].join("."))
+}
+
+/// Number of bytes in the decoded payload of `jwt`.
+pub fn payload_len(jwt: &str) -> usize {
+    ssi::jws::decode_unverified(jwt)
+        .map(|(_, payload)| payload.len())
+        .unwrap_or(0)
+}
+
+pub fn truncate(jwt: &str, len: usize) -> String {
+    jwt[..len.min(jwt.len())].to_string()
+}
+
+fn header(algorithm: Algorithm, kid: &str) -> Header {
+    Header {
+        algorithm,
+        key_id: Some(kid.to_string()),
+        ..Default::default()
+    }
+}
+
+/// Signs `vc` with `key` as `kid`, without checking that the issuer's DID
+/// document authorizes `kid` for assertion, as a misbehaving issuer would.
+pub fn sign_as(vc: &Credential, key: &JWK, kid: &str) -> Result<String> {
+    let algorithm = key.get_algorithm().unwrap_or(Algorithm::EdDSA);
+    sign_with_header(vc, key, header(algorithm, kid))
+}
+
+fn sign_with_header(vc: &Credential, key: &JWK, header: Header) -> Result<String> {
+    let claims = serde_json::to_string(&vc.to_jwt_claims().map_err(encoding)?)?;
+    ssi::jws::encode_sign_custom_header(&claims, key, &header)
+        .map_err(|e| Error::Signing(e.to_string()))
+}
+
+fn encoding(e: impl std::fmt::Display) -> Error {
+    Error::Encoding(e.to_string())
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
@@ -0,0 +1,89 @@
+use std::env;
+use std::process::ExitCode;
+
+use didkemb::fuzz::{self, Tamper};
+
+// fuzz-verify [claims]: tutti i token manomessi devono essere rifiutati
+fn main() -> ExitCode {
+    match didkemb::runtime::block_on(run()) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run() -> ExitCode {
+    let claims: usize = env::args()
+        .nth(1)
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(10);
+    let (key, vc) = match (didkemb::issuer_key(), didkemb::example_credential(claims)) {
+        (Ok(key), Ok(vc)) => (key, vc),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut failures = Vec::new();
+
+    for tamper in Tamper::ALL {
+        match tamper.check(&vc, &key).await {
+            Ok(()) => println!("{:<20} rejected", tamper.name()),
+            Err(e) => {
+                println!("{:<20} FAILED", tamper.name());
+                failures.push(e);
+            }
+        }
+    }
+
+    // Sweep: ogni byte del payload e ogni lunghezza del token
+    let jwt = match fuzz::sign(&vc, &key).await {
+        Ok(jwt) => jwt,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let payload_len = fuzz::payload_len(&jwt);
+    let mut accepted = 0;
+    for i in 0..payload_len {
+        let Some(tampered) = fuzz::flip_payload_byte(&jwt, i) else {
+            continue;
+        };
+        if fuzz::verify(&tampered).await.verified {
+            failures.push(format!("payload byte {} flipped: credential verified", i));
+            accepted += 1;
+        }
+    }
+    println!(
+        "{:<20} {}/{} rejected",
+        "payload-sweep",
+        payload_len - accepted,
+        payload_len
+    );
+
+    let mut accepted = 0;
+    for len in 0..jwt.len() {
+        if fuzz::verify(&fuzz::truncate(&jwt, len)).await.verified {
+            failures.push(format!("truncated to {} bytes: credential verified", len));
+            accepted += 1;
+        }
+    }
+    println!(
+        "{:<20} {}/{} rejected",
+        "truncation-sweep",
+        jwt.len() - accepted,
+        jwt.len()
+    );
+
+    if failures.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        for f in &failures {
+            eprintln!("{}", f);
+        }
+        ExitCode::FAILURE
+    }
+}
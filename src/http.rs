@@ -0,0 +1,167 @@
+//! Minimal JSON-over-HTTP plumbing shared by the issuer and verifier servers:
+//! request logging and per-route latency metrics, served at `GET /metrics`.
+//! Request bodies over [`MAX_BODY`] get `413 Payload Too Large`.
+
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use async_trait::async_trait;
+use hyper::body::HttpBody;
+use hyper::header::CONTENT_LENGTH;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{Error, Result};
+
+/// Largest request body [`serve`] reads.
+pub const MAX_BODY: usize = 1024 * 1024;
+
+/// JSON request handler mounted behind [`serve`].
+#[async_trait]
+pub trait Routes: Send + Sync + 'static {
+    /// Handles one request; `body` is the raw request body.
+    async fn handle(&self, method: &Method, path: &str, body: &[u8]) -> (StatusCode, Value);
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteStats {
+    pub requests: u64,
+    pub errors: u64,
+    pub mean_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    #[serde(skip)]
+    total_ms: f64,
+}
+
+impl RouteStats {
+    fn record(&mut self, ms: f64, error: bool) {
+        if self.requests == 0 || ms < self.min_ms {
+            self.min_ms = ms;
+        }
+        if ms > self.max_ms {
+            self.max_ms = ms;
+        }
+        self.requests += 1;
+        self.errors += error as u64;
+        self.total_ms += ms;
+        self.mean_ms = self.total_ms / self.requests as f64;
+    }
+}
+
+/// Latency and error counts keyed by `"METHOD /path"`.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    routes: Mutex<BTreeMap<String, RouteStats>>,
+}
+
+impl Metrics {
+    pub fn record(&self, route: String, ms: f64, error: bool) {
+        let mut routes = self.routes.lock().unwrap_or_else(|e| e.into_inner());
+        routes.entry(route).or_default().record(ms, error);
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<String, RouteStats> {
+        self.routes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+pub fn json_response(status: StatusCode, body: &Value) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap_or_default()
+}
+
+pub fn error_body(e: impl std::fmt::Display) -> Value {
+    serde_json::json!({ "error": e.to_string() })
+}
+
+/// The body of `req`, read up to [`MAX_BODY`].
+async fn read_body(req: Request<Body>) -> Result<Vec<u8>, (StatusCode, Value)> {
+    let too_large = || {
+        let e = format!("Request body larger than {} bytes", MAX_BODY);
+        (StatusCode::PAYLOAD_TOO_LARGE, error_body(e))
+    };
+    let length = req
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|length| length.to_str().ok()?.parse::<u64>().ok());
+    if length.is_some_and(|length| length > MAX_BODY as u64) {
+        return Err(too_large());
+    }
+    let mut body = req.into_body();
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|e| (StatusCode::BAD_REQUEST, error_body(e)))?;
+        if bytes.len() + chunk.len() > MAX_BODY {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+async fn dispatch<R: Routes>(
+    routes: Arc<R>,
+    metrics: Arc<Metrics>,
+    req: Request<Body>,
+) -> Response<Body> {
+    let start = Instant::now();
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+    let (status, body) = if method == Method::GET && path == "/metrics" {
+        (StatusCode::OK, serde_json::json!(metrics.snapshot()))
+    } else {
+        match read_body(req).await {
+            Ok(bytes) => routes.handle(&method, &path, &bytes).await,
+            Err(response) => response,
+        }
+    };
+    let ms = start.elapsed().as_secs_f64() * 1000.0;
+    metrics.record(
+        format!("{} {}", method, path),
+        ms,
+        status.is_client_error() || status.is_server_error(),
+    );
+    eprintln!("{} {} {} {:.3}ms", method, path, status.as_u16(), ms);
+    json_response(status, &body)
+}
+
+/// Binds `addr` and returns the bound address with the future running the server.
+pub fn serve<R: Routes>(
+    addr: SocketAddr,
+    routes: R,
+) -> Result<(SocketAddr, impl Future<Output = Result<()>>)> {
+    let routes = Arc::new(routes);
+    let metrics = Arc::new(Metrics::default());
+    let make_svc = make_service_fn(move |_| {
+        let routes = routes.clone();
+        let metrics = metrics.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                let routes = routes.clone();
+                let metrics = metrics.clone();
+                async move { Ok::<_, Infallible>(dispatch(routes, metrics, req).await) }
+            }))
+        }
+    });
+    let server = Server::try_bind(&addr)
+        .map_err(|e| Error::Transport(e.to_string()))?
+        .serve(make_svc);
+    let local_addr = server.local_addr();
+    Ok((local_addr, async move {
+        server.await.map_err(|e| Error::Transport(e.to_string()))
+    }))
+}
@@ -0,0 +1,146 @@
+//! Self-certifying identities for device pairing: a key and the did:key,
+//! did:jwk or did:peer derived from it, usable as issuer or as presentation
+//! holder in place of the pre-provisioned [`ISSUER_DID`](crate::ISSUER_DID).
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use ssi::jwk::JWK;
+use ssi::vc::{LinkedDataProofOptions, Presentation, ProofPurpose, URI};
+use ssi_dids::did_resolve::DIDResolver;
+use ssi_dids::{DIDMethod, Source};
+
+use crate::issuer::Issuer;
+use crate::resolver::peer::{DIDPeer, Purpose};
+use crate::resolver::DIDJWK;
+use crate::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Key,
+    Jwk,
+    /// did:peer numalgo 0.
+    Peer0,
+    /// did:peer numalgo 2, with the key listed for assertion and
+    /// authentication.
+    Peer2,
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Method::Key => write!(f, "key"),
+            Method::Jwk => write!(f, "jwk"),
+            Method::Peer0 => write!(f, "peer:0"),
+            Method::Peer2 => write!(f, "peer:2"),
+        }
+    }
+}
+
+/// Parses the [`Display`](fmt::Display) form; `peer` alone is numalgo 0.
+impl FromStr for Method {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "key" => Ok(Method::Key),
+            "jwk" => Ok(Method::Jwk),
+            "peer" | "peer:0" => Ok(Method::Peer0),
+            "peer:2" => Ok(Method::Peer2),
+            _ => Err(Error::Usage(format!("Unknown DID method {}", s))),
+        }
+    }
+}
+
+/// A signing key with its DID and the verification methods to use for
+/// credentials (`assertionMethod`) and presentations (`authentication`).
+#[derive(Debug, Clone)]
+pub struct Identity {
+    pub key: JWK,
+    pub did: String,
+    pub assertion_method: String,
+    pub authentication: String,
+}
+
+impl Identity {
+    /// A fresh Ed25519 key and its DID.
+    pub fn generate(method: Method) -> Result<Self> {
+        let key = JWK::generate_ed25519().map_err(|e| Error::Key(e.to_string()))?;
+        Self::from_key(key, method)
+    }
+
+    /// The DID of `key` under `method`.
+    pub fn from_key(key: JWK, method: Method) -> Result<Self> {
+        let unsupported = || Error::Key(format!("Unsupported key for did:{}", method));
+        let (did, assertion_method, authentication) = match method {
+            Method::Key => {
+                let did = did_method_key::DIDKey
+                    .generate(&Source::Key(&key))
+                    .ok_or_else(unsupported)?;
+                let vm = format!("{}#{}", did, &did["did:key:".len()..]);
+                (did, vm.clone(), vm)
+            }
+            Method::Jwk => {
+                let did = DIDJWK
+                    .generate(&Source::Key(&key))
+                    .ok_or_else(unsupported)?;
+                let vm = DIDJWK::verification_method(&did);
+                (did, vm.clone(), vm)
+            }
+            Method::Peer0 => {
+                let did = DIDPeer::numalgo0(&key).ok_or_else(unsupported)?;
+                let vm = DIDPeer::verification_method(&did).ok_or_else(unsupported)?;
+                (did, vm.clone(), vm)
+            }
+            Method::Peer2 => {
+                let keys = [(Purpose::Assertion, &key), (Purpose::Authentication, &key)];
+                let did = DIDPeer::numalgo2(&keys, &[]).ok_or_else(unsupported)?;
+                let (assertion, authentication) =
+                    (format!("{}#key-1", did), format!("{}#key-2", did));
+                (did, assertion, authentication)
+            }
+        };
+        Ok(Identity {
+            key,
+            did,
+            assertion_method,
+            authentication,
+        })
+    }
+
+    /// Issuer signing as this identity.
+    pub fn issuer(&self, resolver: Arc<dyn DIDResolver + Send>) -> Issuer {
+        Issuer::new(
+            self.key.clone(),
+            self.did.clone(),
+            self.assertion_method.clone(),
+            resolver,
+        )
+    }
+
+    /// Signs a JWT presentation of `credentials` (JWTs) with this identity as
+    /// holder.
+    pub async fn present(
+        &self,
+        credentials: Vec<String>,
+        resolver: &dyn DIDResolver,
+    ) -> Result<String> {
+        let vp: Presentation = serde_json::from_value(serde_json::json!({
+            "@context": ["https://www.w3.org/2018/credentials/v1"],
+            "type": "VerifiablePresentation",
+            "holder": self.did,
+            "verifiableCredential": credentials,
+        }))?;
+        let options = LinkedDataProofOptions {
+            verification_method: Some(URI::String(self.authentication.clone())),
+            proof_purpose: Some(ProofPurpose::Authentication),
+            created: None,
+            checks: None,
+            ..Default::default()
+        };
+        vp.generate_jwt(Some(&self.key), &options, resolver)
+            .await
+            .map_err(|e| Error::Signing(e.to_string()))
+    }
+}
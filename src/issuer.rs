@@ -0,0 +1,193 @@
+//! Credential issuance from a JSON claims payload and a named
+//! [`CredentialTemplate`], as served by the `issuer-server` binary.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use hyper::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use ssi::jwk::JWK;
+use ssi::vc::{Credential, LinkedDataProofOptions, URI};
+use ssi_dids::did_resolve::DIDResolver;
+
+use crate::context::{CREDENTIALS_V1, IOT_V1};
+use crate::datamodel::{self, DataModel};
+use crate::http::{error_body, Routes};
+use crate::schema::SchemaRegistry;
+use crate::template::{CredentialTemplate, Variables};
+use crate::{Error, Result};
+
+pub const DEFAULT_TEMPLATE: &str = "default";
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IssueRequest {
+    /// `credentialSubject.id`, usually the device DID.
+    pub subject: String,
+    #[serde(default)]
+    pub claims: Map<String, Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IssueResponse {
+    pub jwt: String,
+}
+
+pub struct Issuer {
+    key: JWK,
+    issuer: String,
+    options: LinkedDataProofOptions,
+    resolver: Arc<dyn DIDResolver + Send>,
+    templates: BTreeMap<String, CredentialTemplate>,
+    schemas: Option<SchemaRegistry>,
+    data_model: DataModel,
+}
+
+impl Issuer {
+    /// Issuer signing with `key` as `verification_method`, with only the
+    /// default template (the IoT context and the request's claims) installed.
+    pub fn new(
+        key: JWK,
+        issuer: impl Into<String>,
+        verification_method: impl Into<String>,
+        resolver: Arc<dyn DIDResolver + Send>,
+    ) -> Self {
+        let options = LinkedDataProofOptions {
+            verification_method: Some(URI::String(verification_method.into())),
+            created: None,
+            checks: None,
+            ..Default::default()
+        };
+        let mut templates = BTreeMap::new();
+        templates.insert(
+            DEFAULT_TEMPLATE.to_string(),
+            CredentialTemplate {
+                contexts: vec![CREDENTIALS_V1.into(), IOT_V1.into()],
+                issuer: "{{issuer}}".to_string(),
+                ..Default::default()
+            },
+        );
+        Issuer {
+            key,
+            issuer: issuer.into(),
+            options,
+            resolver,
+            templates,
+            schemas: None,
+            data_model: DataModel::V1,
+        }
+    }
+
+    /// Adds (or replaces) templates, such as those of
+    /// [`CredentialTemplate::load_dir`]. Besides their own variables, they
+    /// can use `{{issuer}}`, `{{subject}}` and each claim of the request.
+    pub fn with_templates(mut self, templates: BTreeMap<String, CredentialTemplate>) -> Self {
+        self.templates.extend(templates);
+        self
+    }
+
+    /// Validates credentials naming a `credentialSchema` against `schemas`
+    /// before signing them.
+    pub fn with_schemas(mut self, schemas: SchemaRegistry) -> Self {
+        self.schemas = Some(schemas);
+        self
+    }
+
+    /// Issues VC Data Model 2.0 credentials as `application/vc+jwt` when
+    /// `model` is [`DataModel::V2`].
+    pub fn with_data_model(mut self, model: DataModel) -> Self {
+        self.data_model = model;
+        self
+    }
+
+    /// Switches to a rotated key. The issuer's DID document must already list
+    /// `verification_method` under `assertionMethod`, or issuance fails.
+    pub fn rotate(&mut self, key: JWK, verification_method: impl Into<String>) {
+        self.key = key;
+        self.options.verification_method = Some(URI::String(verification_method.into()));
+    }
+
+    pub fn templates(&self) -> impl Iterator<Item = &str> {
+        self.templates.keys().map(String::as_str)
+    }
+
+    /// Builds the unsigned credential for `req`: the template rendered, then
+    /// the claims merged into its subject, whose `id` is `req.subject`. Claims
+    /// only fill the template's [inputs](CredentialTemplate::inputs); its
+    /// defaults, the built-in variables, `issuer` and `subject` stay as set.
+    pub fn render(&self, req: &IssueRequest) -> Result<Credential> {
+        let name = req.template.as_deref().unwrap_or(DEFAULT_TEMPLATE);
+        let template = self
+            .templates
+            .get(name)
+            .ok_or_else(|| Error::Template(format!("Unknown template {}", name)))?;
+        let inputs = template.inputs();
+        let mut vars: Variables = req
+            .claims
+            .iter()
+            .filter(|(k, _)| inputs.contains(*k))
+            .map(|(k, v)| match v {
+                Value::String(s) => (k.clone(), s.clone()),
+                v => (k.clone(), v.to_string()),
+            })
+            .collect();
+        vars.insert("issuer".to_string(), self.issuer.clone());
+        vars.insert("subject".to_string(), req.subject.clone());
+        let mut vc = template.render_as(&vars, DataModel::V1)?;
+        let subject = vc["credentialSubject"]
+            .as_object_mut()
+            .ok_or_else(|| Error::Template(format!("{}: subject is not a table", name)))?;
+        subject.extend(req.claims.clone());
+        subject.insert("id".to_string(), req.subject.clone().into());
+        vc["issuer"] = self.issuer.clone().into();
+        serde_json::from_value(vc).map_err(|e| Error::Template(e.to_string()))
+    }
+
+    pub async fn issue(&self, req: &IssueRequest) -> Result<String> {
+        let vc = self.render(req)?;
+        if let Some(ref schemas) = self.schemas {
+            schemas.validate(&vc).map_err(Error::Schema)?;
+        }
+        match self.data_model {
+            DataModel::V1 => {
+                crate::issue_jwt(&vc, &self.key, &self.options, self.resolver.as_ref()).await
+            }
+            DataModel::V2 => {
+                let vc = datamodel::to_v2(serde_json::to_value(vc)?);
+                datamodel::issue_vc_jwt(&vc, &self.key, &self.options)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Routes for Issuer {
+    async fn handle(&self, method: &Method, path: &str, body: &[u8]) -> (StatusCode, Value) {
+        match (method, path) {
+            (&Method::POST, "/credentials/issue") => {
+                let req: IssueRequest = match serde_json::from_slice(body) {
+                    Ok(req) => req,
+                    Err(e) => return (StatusCode::BAD_REQUEST, error_body(e)),
+                };
+                match self.issue(&req).await {
+                    Ok(jwt) => (StatusCode::OK, serde_json::json!(IssueResponse { jwt })),
+                    Err(e @ Error::Template(_)) => (StatusCode::BAD_REQUEST, error_body(e)),
+                    Err(Error::Schema(errors)) => {
+                        let mut body = error_body(Error::Schema(errors.clone()));
+                        body["errors"] = serde_json::json!(errors);
+                        (StatusCode::BAD_REQUEST, body)
+                    }
+                    Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, error_body(e)),
+                }
+            }
+            (&Method::GET, "/templates") => (
+                StatusCode::OK,
+                serde_json::json!(self.templates().collect::<Vec<_>>()),
+            ),
+            _ => (StatusCode::NOT_FOUND, error_body("Not found")),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use didkemb::cli::Args;
+use didkemb::datamodel::DataModel;
+use didkemb::identity::{Identity, Method};
+use didkemb::issuer::Issuer;
+use didkemb::resolver;
+use didkemb::schema::SchemaRegistry;
+use didkemb::template::CredentialTemplate;
+
+// issuer-server [--addr 127.0.0.1:8080] [--templates templates/]
+//               [--key chiave.json --issuer DID --verification-method VM]
+//               [--did-method key|jwk|peer:0|peer:2] [--schemas schemas/]
+//               [--data-model 1.1|2.0]
+// Con --did-method il DID dell'emittente viene derivato dalla chiave. Con
+// --schemas le credenziali con credentialSchema vengono validate prima della
+// firma. Con --data-model 2.0 le credenziali usano il contesto v2,
+// validFrom/validUntil e l'involucro application/vc+jwt.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    didkemb::runtime::block_on(run())?
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::from_env();
+    let addr: SocketAddr = args.parse_or("addr", SocketAddr::from(([127, 0, 0, 1], 8080)))?;
+    let key = match args.value("key") {
+        Some(path) => didkemb::load_key(path)?,
+        None => didkemb::issuer_key()?,
+    };
+    let resolver = Arc::new(resolver::default_chain());
+
+    let mut issuer = match args.value("did-method") {
+        Some(method) => Identity::from_key(key, method.parse::<Method>()?)?.issuer(resolver),
+        None => Issuer::new(
+            key,
+            args.value("issuer").unwrap_or(didkemb::ISSUER_DID),
+            args.value("verification-method")
+                .unwrap_or(didkemb::VERIFICATION_METHOD),
+            resolver,
+        ),
+    };
+    if let Some(dir) = args.value("templates") {
+        issuer = issuer.with_templates(CredentialTemplate::load_dir(dir)?);
+    }
+    if let Some(dir) = args.value("schemas") {
+        issuer = issuer.with_schemas(SchemaRegistry::from_dir(dir)?);
+    }
+    issuer = issuer.with_data_model(args.parse_or("data-model", DataModel::V1)?);
+    let templates: Vec<&str> = issuer.templates().collect();
+    eprintln!("templates: {}", templates.join(", "));
+
+    let (addr, server) = didkemb::http::serve(addr, issuer)?;
+    eprintln!("issuer-server listening on http://{}", addr);
+    server.await?;
+    Ok(())
+}
@@ -0,0 +1,118 @@
+//! Shared pieces of the didkitembedded benchmarks: the pre-provisioned issuer
+//! key and DID document, and helpers used by the `NNclaim` binaries.
+
+pub mod chain;
+pub mod cli;
+pub mod coap;
+pub mod context;
+pub mod datamodel;
+pub mod digest;
+pub mod document;
+pub mod error;
+pub mod fuzz;
+pub mod http;
+pub mod identity;
+pub mod issuer;
+pub mod measure;
+pub mod merkle;
+pub mod mqtt;
+pub mod report;
+pub mod resolver;
+pub mod runtime;
+pub mod schema;
+pub mod template;
+pub mod throughput;
+pub mod trust;
+pub mod verifier;
+
+pub use error::{Error, Result};
+pub use resolver::DIDExampleStatic;
+
+use ssi::jsonld::ContextLoader;
+use ssi::jwk::JWK;
+use ssi::vc::{Credential, LinkedDataProofOptions, ProofPurpose};
+use ssi_dids::did_resolve::DIDResolver;
+
+pub const ISSUER_DID: &str = "did:key:z6MkjF6Srb2uTSHVtjA53e59pUWJEY2QZzMkh9w198mhZmzB";
+pub const VERIFICATION_METHOD: &str = "did:key:z6MkjF6Srb2uTSHVtjA53e59pUWJEY2QZzMkh9w198mhZmzB#z6MkjF6Srb2uTSHVtjA53e59pUWJEY2QZzMkh9w198mhZmzB";
+
+/// Issuer key matching the verification method in `did-example-mine.json`.
+pub fn issuer_key() -> Result<JWK> {
+    serde_json::from_str(include_str!("../chiave_str.json")).map_err(|e| Error::Key(e.to_string()))
+}
+
+/// Reads a JWK from a JSON file.
+pub fn load_key(path: impl AsRef<std::path::Path>) -> Result<JWK> {
+    let file = std::fs::read_to_string(path.as_ref())
+        .map_err(|e| Error::Key(format!("{}: {}", path.as_ref().display(), e)))?;
+    serde_json::from_str(&file).map_err(|e| Error::Key(e.to_string()))
+}
+
+/// JWT proof options pinned to `VERIFICATION_METHOD`, for credentials.
+pub fn proof_options() -> LinkedDataProofOptions {
+    proof_options_for(ProofPurpose::AssertionMethod)
+}
+
+/// JWT proof options pinned to `VERIFICATION_METHOD` for `purpose`:
+/// `AssertionMethod` for credentials, `Authentication` for presentations. The
+/// method must be listed under that relationship in the signer's DID document.
+pub fn proof_options_for(purpose: ProofPurpose) -> LinkedDataProofOptions {
+    LinkedDataProofOptions {
+        verification_method: Some(ssi::vc::URI::String(VERIFICATION_METHOD.to_string())),
+        proof_purpose: Some(purpose),
+        created: None,
+        checks: None,
+        ..Default::default()
+    }
+}
+
+/// `@context` of the benchmark credentials: the IoT context
+/// ([`context::IOT_V1`]), so LDP proofs need [`context::loader`].
+pub fn benchmark_context() -> serde_json::Value {
+    context::iot()
+}
+
+/// Credential body used by the benchmarks, with `claims` entries `claimN: "value N"`:
+/// the [`template::BENCHMARK`] template issued by [`ISSUER_DID`].
+pub fn example_credential(claims: usize) -> Result<Credential> {
+    let vars = template::Variables::from([
+        ("issuer".to_string(), ISSUER_DID.to_string()),
+        ("claims".to_string(), claims.to_string()),
+    ]);
+    template::CredentialTemplate::benchmark().render(&vars)
+}
+
+/// Signs `vc` as a JWT with `key`.
+pub async fn issue_jwt(
+    vc: &Credential,
+    key: &JWK,
+    options: &LinkedDataProofOptions,
+    resolver: &dyn DIDResolver,
+) -> Result<String> {
+    vc.generate_jwt(Some(key), options, resolver)
+        .await
+        .map_err(|e| Error::Signing(e.to_string()))
+}
+
+/// Signs `vc` with a Linked Data proof from `key`, returning it with the
+/// proof attached.
+pub async fn issue_ldp(
+    vc: &Credential,
+    key: &JWK,
+    options: &LinkedDataProofOptions,
+    resolver: &dyn DIDResolver,
+    context_loader: &mut ContextLoader,
+) -> Result<Credential> {
+    let proof = vc
+        .generate_proof(key, options, resolver, context_loader)
+        .await
+        .map_err(|e| Error::Signing(e.to_string()))?;
+    let mut vc = vc.clone();
+    vc.add_proof(proof);
+    Ok(vc)
+}
+
+/// Decodes a JWT credential, checking its signature against `key` only.
+pub fn decode_jwt(jwt: &str, key: &JWK) -> Result<Credential> {
+    Credential::from_jwt(jwt, key).map_err(|e| Error::Encoding(e.to_string()))
+}
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use psutil::process::Process;
+
+use crate::Result;
+
+/// CPU time and resident memory of the current process at one instant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Usage {
+    pub cpu_user: Duration,
+    pub rss: u64,
+    pub threads: u64,
+}
+
+impl Usage {
+    /// Usage accumulated since `earlier`; RSS may shrink, so it saturates at 0.
+    pub fn since(&self, earlier: &Usage) -> Usage {
+        Usage {
+            cpu_user: self.cpu_user.saturating_sub(earlier.cpu_user),
+            rss: self.rss.saturating_sub(earlier.rss),
+            threads: self.threads,
+        }
+    }
+}
+
+pub struct ProcessMonitor {
+    process: Process,
+}
+
+impl ProcessMonitor {
+    pub fn current() -> Result<Self> {
+        Ok(ProcessMonitor {
+            process: Process::current()?,
+        })
+    }
+
+    pub fn sample(&self) -> Result<Usage> {
+        Ok(Usage {
+            cpu_user: self.process.cpu_times()?.user(),
+            rss: self.process.memory_info()?.rss(),
+            threads: self.threads(),
+        })
+    }
+
+    /// Entries in `/proc/<pid>/task`; psutil's `num_threads` is unimplemented on
+    /// Linux. 0 where procfs is unavailable.
+    fn threads(&self) -> u64 {
+        std::fs::read_dir(format!("/proc/{}/task", self.process.pid()))
+            .map_or(0, |tasks| tasks.count() as u64)
+    }
+}
+
+/// Distribution of a set of latency samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencySummary {
+    pub count: usize,
+    pub min: Duration,
+    pub mean: Duration,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl LatencySummary {
+    /// `None` when there are no samples.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_unstable();
+        let count = samples.len();
+        // Nearest-rank percentile.
+        let rank = |p: usize| samples[(count * p).div_ceil(100).max(1) - 1];
+        Some(LatencySummary {
+            count,
+            min: samples[0],
+            mean: samples.iter().sum::<Duration>() / count as u32,
+            p50: rank(50),
+            p95: rank(95),
+            p99: rank(99),
+            max: samples[count - 1],
+        })
+    }
+}
@@ -0,0 +1,226 @@
+//! Batched signing: a Merkle tree over the digests of many readings, one
+//! credential attesting the root, and an inclusion proof per reading so each
+//! one can be checked on its own.
+//!
+//! Leaves are `H(0x00 || record)` and inner nodes `H(0x01 || left || right)`,
+//! so a leaf can never be passed off as an inner node. An unpaired node is
+//! promoted to the next level unchanged.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::digest::{from_hex, to_hex, HashAlgorithm};
+use crate::issuer::{IssueRequest, Issuer};
+use crate::{Error, Result};
+
+const LEAF: &[u8] = &[0x00];
+const NODE: &[u8] = &[0x01];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// A sibling on the path from a leaf to the root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofStep {
+    /// Which side the sibling sits on.
+    pub side: Side,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InclusionProof {
+    pub index: usize,
+    pub leaf_count: usize,
+    pub path: Vec<ProofStep>,
+}
+
+impl InclusionProof {
+    /// Root obtained by hashing `record` up along the path. The path must have
+    /// the shape implied by `index` and `leaf_count`, so a proof cannot be
+    /// replayed for another position.
+    pub fn root(&self, algorithm: HashAlgorithm, record: &[u8]) -> Result<Vec<u8>> {
+        if self.index >= self.leaf_count {
+            return Err(Error::Payload(format!(
+                "Index {} outside a batch of {}",
+                self.index, self.leaf_count
+            )));
+        }
+        let mut node = algorithm.digest(&[LEAF, record]);
+        let mut steps = self.path.iter();
+        let (mut i, mut n) = (self.index, self.leaf_count);
+        while n > 1 {
+            if i ^ 1 < n {
+                let expected = if i % 2 == 1 { Side::Left } else { Side::Right };
+                let step = match steps.next() {
+                    Some(step) if step.side == expected => step,
+                    _ => return Err(Error::Payload("Malformed inclusion proof".to_string())),
+                };
+                let sibling = from_hex(&step.hash)?;
+                node = match step.side {
+                    Side::Left => algorithm.digest(&[NODE, &sibling, &node]),
+                    Side::Right => algorithm.digest(&[NODE, &node, &sibling]),
+                };
+            }
+            i /= 2;
+            n = n.div_ceil(2);
+        }
+        if steps.next().is_some() {
+            return Err(Error::Payload("Malformed inclusion proof".to_string()));
+        }
+        Ok(node)
+    }
+}
+
+pub struct MerkleTree {
+    algorithm: HashAlgorithm,
+    /// `levels[0]` are the leaves, the last level holds only the root.
+    levels: Vec<Vec<Vec<u8>>>,
+}
+
+impl MerkleTree {
+    pub fn new<R: AsRef<[u8]>>(algorithm: HashAlgorithm, records: &[R]) -> Result<Self> {
+        if records.is_empty() {
+            return Err(Error::Payload(
+                "Cannot build a Merkle tree of no records".to_string(),
+            ));
+        }
+        let mut levels = vec![records
+            .iter()
+            .map(|r| algorithm.digest(&[LEAF, r.as_ref()]))
+            .collect::<Vec<_>>()];
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => algorithm.digest(&[NODE, left, right]),
+                    [single] => single.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Ok(MerkleTree { algorithm, levels })
+    }
+
+    pub fn algorithm(&self) -> HashAlgorithm {
+        self.algorithm
+    }
+
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.levels[0].is_empty()
+    }
+
+    pub fn root(&self) -> &[u8] {
+        &self.levels[self.levels.len() - 1][0]
+    }
+
+    pub fn proof(&self, index: usize) -> Option<InclusionProof> {
+        if index >= self.len() {
+            return None;
+        }
+        let mut path = Vec::new();
+        let mut i = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = i ^ 1;
+            if let Some(hash) = level.get(sibling) {
+                path.push(ProofStep {
+                    side: if sibling < i { Side::Left } else { Side::Right },
+                    hash: to_hex(hash),
+                });
+            }
+            i /= 2;
+        }
+        Some(InclusionProof {
+            index,
+            leaf_count: self.len(),
+            path,
+        })
+    }
+
+    /// `merkleRoot`, `hashAlgorithm` and `leafCount` for the credential subject.
+    pub fn claims(&self) -> Map<String, Value> {
+        let mut claims = Map::new();
+        claims.insert("merkleRoot".to_string(), to_hex(self.root()).into());
+        claims.insert(
+            "hashAlgorithm".to_string(),
+            serde_json::json!(self.algorithm),
+        );
+        claims.insert("leafCount".to_string(), self.len().into());
+        claims
+    }
+}
+
+/// Root and algorithm attested by a batch credential subject.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchRoot {
+    pub merkle_root: String,
+    pub hash_algorithm: HashAlgorithm,
+    pub leaf_count: usize,
+}
+
+impl BatchRoot {
+    pub fn from_subject(subject: &Value) -> Result<Self> {
+        serde_json::from_value(subject.clone())
+            .map_err(|e| Error::Payload(format!("Not a batch credential: {}", e)))
+    }
+
+    /// Checks that `record` sits at `proof.index` under this root.
+    pub fn check(&self, record: &[u8], proof: &InclusionProof) -> Result<(), String> {
+        if proof.leaf_count != self.leaf_count {
+            return Err(format!(
+                "Proof is for a batch of {}, credential attests {}",
+                proof.leaf_count, self.leaf_count
+            ));
+        }
+        let root = proof
+            .root(self.hash_algorithm, record)
+            .map_err(|e| e.to_string())?;
+        if to_hex(&root) != self.merkle_root.to_ascii_lowercase() {
+            return Err(format!(
+                "Record {} does not hash to the attested root {}",
+                proof.index, self.merkle_root
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// One signed credential for a whole batch plus a proof per record.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedBatch {
+    pub jwt: String,
+    pub proofs: Vec<InclusionProof>,
+}
+
+/// Serializes each record to compact JSON, builds the tree and signs its root
+/// with `issuer` as a credential about `subject`.
+pub async fn issue_batch<T: Serialize>(
+    issuer: &Issuer,
+    subject: impl Into<String>,
+    records: &[T],
+    algorithm: HashAlgorithm,
+) -> Result<SignedBatch> {
+    let records = records
+        .iter()
+        .map(serde_json::to_vec)
+        .collect::<Result<Vec<_>, _>>()?;
+    let tree = MerkleTree::new(algorithm, &records)?;
+    let req = IssueRequest {
+        subject: subject.into(),
+        claims: tree.claims(),
+        template: None,
+    };
+    let jwt = issuer.issue(&req).await?;
+    let proofs = (0..tree.len()).filter_map(|i| tree.proof(i)).collect();
+    Ok(SignedBatch { jwt, proofs })
+}
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use didkemb::cli::Args;
+use didkemb::digest::HashAlgorithm;
+use didkemb::issuer::Issuer;
+use didkemb::merkle;
+use didkemb::mqtt::SensorReading;
+use didkemb::DIDExampleStatic;
+
+// merkle-bench [--readings 1,10,100] [--algorithm sha-256|sha3-256|blake3]
+// Confronta una JWT per lettura con una sola JWT sulla radice del Merkle tree.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    didkemb::runtime::block_on(run())?
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::from_env();
+    let algorithm: HashAlgorithm = args.parse_or("algorithm", HashAlgorithm::default())?;
+    let sizes = args
+        .value("readings")
+        .unwrap_or("1,10,100")
+        .split(',')
+        .map(|n| {
+            n.trim()
+                .parse::<u64>()
+                .map_err(|e| didkemb::Error::Usage(format!("--readings {}: {}", n, e)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let issuer = Issuer::new(
+        didkemb::issuer_key()?,
+        didkemb::ISSUER_DID,
+        didkemb::VERIFICATION_METHOD,
+        Arc::new(DIDExampleStatic),
+    );
+    let sensor = "urn:uuid:".to_string() + &uuid::Uuid::new_v4().to_string();
+
+    println!("readings,per_reading_ms,batch_total_ms,batch_amortized_ms,speedup,jwt_bytes,avg_proof_bytes");
+    for n in sizes {
+        let readings: Vec<_> = (0..n)
+            .map(|i| SensorReading::now(sensor.as_str(), i, 20.0 + i as f64 / 100.0, "celsius"))
+            .collect();
+
+        let start = Instant::now();
+        for reading in &readings {
+            issuer.issue(&reading.issue_request(None)?).await?;
+        }
+        let per_reading = start.elapsed().as_secs_f64() * 1000.0 / n as f64;
+
+        let start = Instant::now();
+        let batch = merkle::issue_batch(&issuer, sensor.as_str(), &readings, algorithm).await?;
+        let batch_total = start.elapsed().as_secs_f64() * 1000.0;
+
+        let proof_bytes: usize = batch
+            .proofs
+            .iter()
+            .map(|p| serde_json::to_vec(p).map_or(0, |v| v.len()))
+            .sum();
+        println!(
+            "{},{:.3},{:.3},{:.4},{:.1},{},{}",
+            n,
+            per_reading,
+            batch_total,
+            batch_total / n as f64,
+            per_reading / (batch_total / n as f64),
+            batch.jwt.len(),
+            proof_bytes / n as usize,
+        );
+    }
+    Ok(())
+}
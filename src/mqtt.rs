@@ -0,0 +1,388 @@
+//! MQTT transport for signed sensor readings: every reading is issued as its
+//! own credential and the JWT is published on a topic, where a subscriber
+//! verifies each message as it arrives.
+//!
+//! [`serve`] runs a minimal MQTT 3.1.1 broker (QoS 0 and 1, no retained
+//! messages or sessions) so publisher and subscriber can be exercised without
+//! an external broker.
+
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bytes::BytesMut;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rumqttc::mqttbytes::{self, matches, QoS};
+use rumqttc::{
+    AsyncClient, ConnAck, ConnectReturnCode, Event, EventLoop, MqttOptions, Packet, PingResp,
+    PubAck, Publish, SubAck, SubscribeReasonCode,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+use crate::datamodel;
+use crate::digest::{HashAlgorithm, PayloadDigest};
+use crate::issuer::{IssueRequest, Issuer};
+use crate::report::VerificationReport;
+use crate::verifier::Verifier;
+use crate::{Error, Result};
+
+pub const DEFAULT_TOPIC: &str = "sensors/readings";
+
+/// Largest packet accepted by the broker and the clients; JWTs with a few
+/// hundred claims stay well below it.
+const MAX_PACKET_SIZE: usize = 256 * 1024;
+
+/// Requests buffered by a client before `publish` waits for the event loop.
+const CLIENT_CAPACITY: usize = 64;
+
+fn transport(e: impl std::fmt::Display) -> Error {
+    Error::Transport(e.to_string())
+}
+
+/// One sample taken by a sensor. It becomes the subject of the credential:
+/// `sensor` is `credentialSubject.id`, the other fields are claims.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SensorReading {
+    pub sensor: String,
+    pub sequence: u64,
+    pub value: f64,
+    pub unit: String,
+    /// RFC 3339 with nanoseconds, taken when the reading was made.
+    pub timestamp: String,
+}
+
+impl SensorReading {
+    /// Reading stamped with the current time.
+    pub fn now(
+        sensor: impl Into<String>,
+        sequence: u64,
+        value: f64,
+        unit: impl Into<String>,
+    ) -> Self {
+        SensorReading {
+            sensor: sensor.into(),
+            sequence,
+            value,
+            unit: unit.into(),
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Nanos, true),
+        }
+    }
+
+    /// Request carrying the reading as claims, plus the SHA-256 digest of the
+    /// record so it can be checked against a copy stored elsewhere.
+    pub fn issue_request(&self, template: Option<String>) -> Result<IssueRequest> {
+        let mut claims = PayloadDigest::of_record(HashAlgorithm::Sha256, self)?.claims();
+        claims.insert("sequence".to_string(), self.sequence.into());
+        claims.insert("value".to_string(), self.value.into());
+        claims.insert("unit".to_string(), self.unit.clone().into());
+        claims.insert("timestamp".to_string(), self.timestamp.clone().into());
+        Ok(IssueRequest {
+            subject: self.sensor.clone(),
+            claims,
+            template,
+        })
+    }
+
+    /// Reads the reading back out of a JWT credential, in either data model,
+    /// without verifying it.
+    pub fn from_jwt(jwt: &str) -> Result<Self> {
+        let mut vc = datamodel::decode_unverified(jwt).map_err(Error::Encoding)?;
+        let mut subject = match vc["credentialSubject"].take() {
+            Value::Object(subject) => subject,
+            _ => return Err(Error::Encoding("No credentialSubject".to_string())),
+        };
+        if let Some(id) = subject.remove("id") {
+            subject.insert("sensor".to_string(), id);
+        }
+        Ok(serde_json::from_value(subject.into())?)
+    }
+
+    /// Time elapsed since the reading was taken; `None` if the timestamp does
+    /// not parse or lies in the future (clocks out of sync).
+    pub fn age(&self) -> Option<Duration> {
+        let taken = DateTime::parse_from_rfc3339(&self.timestamp).ok()?;
+        Utc::now().signed_duration_since(taken).to_std().ok()
+    }
+}
+
+/// MQTT client options shared by publisher and subscriber.
+pub fn options(client_id: impl Into<String>, broker: SocketAddr) -> MqttOptions {
+    let mut options = MqttOptions::new(client_id, broker.ip().to_string(), broker.port());
+    options.set_max_packet_size(MAX_PACKET_SIZE, MAX_PACKET_SIZE);
+    options
+}
+
+/// Signs readings with an [`Issuer`] and publishes the JWTs with QoS 1.
+pub struct SensorPublisher {
+    client: AsyncClient,
+    issuer: Issuer,
+    topic: String,
+    template: Option<String>,
+}
+
+impl SensorPublisher {
+    /// Connects with `options`; the client's event loop runs on its own task.
+    pub fn connect(options: MqttOptions, issuer: Issuer, topic: impl Into<String>) -> Self {
+        let (client, mut eventloop) = AsyncClient::new(options, CLIENT_CAPACITY);
+        tokio::spawn(async move {
+            loop {
+                match eventloop.poll().await {
+                    Ok(Event::Outgoing(rumqttc::Outgoing::Disconnect)) => break,
+                    Ok(_) => {}
+                    Err(e) => {
+                        eprintln!("mqtt publisher: {}", e);
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                }
+            }
+        });
+        SensorPublisher {
+            client,
+            issuer,
+            topic: topic.into(),
+            template: None,
+        }
+    }
+
+    /// Issues readings from `template` instead of the default one.
+    pub fn with_template(mut self, template: impl Into<String>) -> Self {
+        self.template = Some(template.into());
+        self
+    }
+
+    /// Signs `reading` and queues it for publishing; returns the JWT.
+    pub async fn publish(&self, reading: &SensorReading) -> Result<String> {
+        let jwt = self
+            .issuer
+            .issue(&reading.issue_request(self.template.clone())?)
+            .await?;
+        self.client
+            .publish(&self.topic, QoS::AtLeastOnce, false, jwt.clone())
+            .await
+            .map_err(transport)?;
+        Ok(jwt)
+    }
+
+    pub async fn disconnect(&self) -> Result<()> {
+        self.client.disconnect().await.map_err(transport)
+    }
+}
+
+/// A message received by [`VerifyingSubscriber`].
+#[derive(Debug, Clone)]
+pub struct VerifiedReading {
+    pub topic: String,
+    pub jwt: String,
+    pub report: VerificationReport,
+    /// `None` if the payload is not a sensor reading credential.
+    pub reading: Option<SensorReading>,
+    /// From the reading's timestamp to the end of verification.
+    pub latency: Option<Duration>,
+}
+
+/// Subscribes to a topic filter and verifies every JWT published on it.
+pub struct VerifyingSubscriber {
+    client: AsyncClient,
+    eventloop: EventLoop,
+    verifier: Verifier,
+}
+
+impl VerifyingSubscriber {
+    /// Connects and returns once the broker has acknowledged the subscription,
+    /// so nothing published afterwards is missed.
+    pub async fn connect(options: MqttOptions, filter: &str, verifier: Verifier) -> Result<Self> {
+        let (client, mut eventloop) = AsyncClient::new(options, CLIENT_CAPACITY);
+        client
+            .subscribe(filter, QoS::AtMostOnce)
+            .await
+            .map_err(transport)?;
+        loop {
+            match eventloop.poll().await.map_err(transport)? {
+                Event::Incoming(Packet::SubAck(ack)) => match ack.return_codes.first() {
+                    Some(SubscribeReasonCode::Success(_)) => break,
+                    _ => return Err(transport(format!("Subscription to {} refused", filter))),
+                },
+                _ => continue,
+            }
+        }
+        Ok(VerifyingSubscriber {
+            client,
+            eventloop,
+            verifier,
+        })
+    }
+
+    /// Waits for the next message and verifies it.
+    pub async fn next(&mut self) -> Result<VerifiedReading> {
+        let publish = loop {
+            if let Event::Incoming(Packet::Publish(publish)) =
+                self.eventloop.poll().await.map_err(transport)?
+            {
+                break publish;
+            }
+        };
+        let jwt = String::from_utf8_lossy(&publish.payload).into_owned();
+        let report = self.verifier.verify(&jwt).await;
+        let reading = SensorReading::from_jwt(&jwt).ok();
+        let latency = reading.as_ref().and_then(SensorReading::age);
+        Ok(VerifiedReading {
+            topic: publish.topic,
+            jwt,
+            report,
+            reading,
+            latency,
+        })
+    }
+
+    pub async fn disconnect(&self) -> Result<()> {
+        self.client.disconnect().await.map_err(transport)
+    }
+}
+
+struct Subscription {
+    filter: String,
+    connection: u64,
+    outbox: mpsc::UnboundedSender<Packet>,
+}
+
+#[derive(Default)]
+struct Broker {
+    subscriptions: Mutex<Vec<Subscription>>,
+    connections: AtomicU64,
+}
+
+impl Broker {
+    /// Forwards `publish` to every matching subscription, downgraded to QoS 0.
+    fn route(&self, publish: &Publish) {
+        let subscriptions = self.subscriptions.lock().unwrap();
+        for subscription in subscriptions.iter() {
+            if matches(&publish.topic, &subscription.filter) {
+                let forward = Publish::new(
+                    publish.topic.clone(),
+                    QoS::AtMostOnce,
+                    publish.payload.to_vec(),
+                );
+                let _ = subscription.outbox.send(Packet::Publish(forward));
+            }
+        }
+    }
+
+    async fn connection(self: Arc<Self>, stream: TcpStream) -> Result<()> {
+        let id = self.connections.fetch_add(1, Ordering::Relaxed);
+        let (mut reader, mut writer) = stream.into_split();
+        let (outbox, mut outgoing) = mpsc::unbounded_channel();
+        let writer = tokio::spawn(async move {
+            let mut buf = BytesMut::new();
+            while let Some(packet) = outgoing.recv().await {
+                encode(&packet, &mut buf)?;
+                writer.write_all(&buf).await.map_err(transport)?;
+                buf.clear();
+            }
+            Ok::<_, Error>(())
+        });
+
+        let result = self.read_loop(id, &mut reader, &outbox).await;
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .retain(|s| s.connection != id);
+        drop(outbox);
+        let _ = writer.await;
+        result
+    }
+
+    async fn read_loop(
+        &self,
+        id: u64,
+        reader: &mut tokio::net::tcp::OwnedReadHalf,
+        outbox: &mpsc::UnboundedSender<Packet>,
+    ) -> Result<()> {
+        let mut buf = BytesMut::with_capacity(4096);
+        loop {
+            let packet = match rumqttc::mqttbytes::v4::read(&mut buf, MAX_PACKET_SIZE) {
+                Ok(packet) => packet,
+                Err(mqttbytes::Error::InsufficientBytes(_)) => {
+                    if reader.read_buf(&mut buf).await.map_err(transport)? == 0 {
+                        return Ok(());
+                    }
+                    continue;
+                }
+                Err(e) => return Err(transport(e)),
+            };
+            let reply = match packet {
+                Packet::Connect(_) => {
+                    Packet::ConnAck(ConnAck::new(ConnectReturnCode::Success, false))
+                }
+                Packet::Subscribe(subscribe) => {
+                    let mut codes = Vec::with_capacity(subscribe.filters.len());
+                    let mut subscriptions = self.subscriptions.lock().unwrap();
+                    for filter in subscribe.filters {
+                        if mqttbytes::valid_filter(&filter.path) {
+                            subscriptions.push(Subscription {
+                                filter: filter.path,
+                                connection: id,
+                                outbox: outbox.clone(),
+                            });
+                            codes.push(SubscribeReasonCode::Success(QoS::AtMostOnce));
+                        } else {
+                            codes.push(SubscribeReasonCode::Failure);
+                        }
+                    }
+                    Packet::SubAck(SubAck::new(subscribe.pkid, codes))
+                }
+                Packet::Publish(publish) => {
+                    self.route(&publish);
+                    match publish.qos {
+                        QoS::AtMostOnce => continue,
+                        QoS::AtLeastOnce => Packet::PubAck(PubAck::new(publish.pkid)),
+                        QoS::ExactlyOnce => return Err(transport("QoS 2 is not supported")),
+                    }
+                }
+                Packet::PingReq => Packet::PingResp,
+                Packet::Disconnect => return Ok(()),
+                _ => continue,
+            };
+            if outbox.send(reply).is_err() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn encode(packet: &Packet, buf: &mut BytesMut) -> Result<usize> {
+    match packet {
+        Packet::ConnAck(p) => p.write(buf),
+        Packet::SubAck(p) => p.write(buf),
+        Packet::PubAck(p) => p.write(buf),
+        Packet::Publish(p) => p.write(buf),
+        Packet::PingResp => PingResp.write(buf),
+        other => return Err(transport(format!("Broker does not send {:?}", other))),
+    }
+    .map_err(transport)
+}
+
+/// Binds the embedded broker to `addr` and returns the bound address together
+/// with the accept loop.
+pub async fn serve(addr: SocketAddr) -> Result<(SocketAddr, impl Future<Output = Result<()>>)> {
+    let listener = TcpListener::bind(addr).await.map_err(transport)?;
+    let local = listener.local_addr().map_err(transport)?;
+    let broker = Arc::new(Broker::default());
+    let accept = async move {
+        loop {
+            let (stream, peer) = listener.accept().await.map_err(transport)?;
+            let broker = broker.clone();
+            tokio::spawn(async move {
+                if let Err(e) = broker.connection(stream).await {
+                    eprintln!("mqtt {}: {}", peer, e);
+                }
+            });
+        }
+    };
+    Ok((local, accept))
+}
@@ -0,0 +1,110 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use didkemb::cli::Args;
+use didkemb::issuer::Issuer;
+use didkemb::measure::LatencySummary;
+use didkemb::mqtt::{self, SensorPublisher, SensorReading, VerifyingSubscriber};
+use didkemb::verifier::Verifier;
+use didkemb::DIDExampleStatic;
+
+fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+// mqtt-bench [--broker ADDR] [--topic sensors/readings] [--readings 100]
+// Senza --broker avvia un broker e un sensore locali; con --broker verifica
+// le letture pubblicate da mqtt-sensor.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    didkemb::runtime::block_on(run())?
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::from_env();
+    let topic = args
+        .value("topic")
+        .unwrap_or(mqtt::DEFAULT_TOPIC)
+        .to_string();
+    let readings: u64 = args.parse_or("readings", 100)?;
+    let (broker, local) = match args.value("broker") {
+        Some(addr) => (addr.parse::<SocketAddr>()?, false),
+        None => {
+            let (addr, broker) = mqtt::serve(([127, 0, 0, 1], 0).into()).await?;
+            tokio::spawn(broker);
+            (addr, true)
+        }
+    };
+
+    let verifier = Verifier::new(Arc::new(DIDExampleStatic));
+    let mut subscriber = VerifyingSubscriber::connect(
+        mqtt::options(format!("bench-{}", std::process::id()), broker),
+        &topic,
+        verifier,
+    )
+    .await?;
+
+    if local {
+        let issuer = Issuer::new(
+            didkemb::issuer_key()?,
+            didkemb::ISSUER_DID,
+            didkemb::VERIFICATION_METHOD,
+            Arc::new(DIDExampleStatic),
+        );
+        let publisher = SensorPublisher::connect(
+            mqtt::options(format!("sensor-{}", std::process::id()), broker),
+            issuer,
+            topic.clone(),
+        );
+        let sensor = "urn:uuid:".to_string() + &uuid::Uuid::new_v4().to_string();
+        tokio::spawn(async move {
+            for sequence in 0..readings {
+                let reading = SensorReading::now(sensor.as_str(), sequence, 20.0, "celsius");
+                if let Err(e) = publisher.publish(&reading).await {
+                    eprintln!("publish {}: {}", sequence, e);
+                }
+            }
+        });
+    }
+
+    println!("sequence,jwt_bytes,verified,latency_ms");
+    let mut latencies = Vec::new();
+    let mut failed = 0;
+    let mut start = None;
+    for _ in 0..readings {
+        let message = subscriber.next().await?;
+        start.get_or_insert_with(Instant::now);
+        if !message.report.verified {
+            failed += 1;
+        }
+        if let Some(latency) = message.latency {
+            latencies.push(latency);
+        }
+        println!(
+            "{},{},{},{}",
+            message.reading.map_or(-1, |r| r.sequence as i64),
+            message.jwt.len(),
+            message.report.verified,
+            message
+                .latency
+                .map_or(String::new(), |l| format!("{:.3}", ms(l))),
+        );
+    }
+    let elapsed = start.map_or(Duration::ZERO, |s| s.elapsed());
+    subscriber.disconnect().await?;
+
+    eprintln!("readings: {} ({} failed verification)", readings, failed);
+    if readings > 1 && !elapsed.is_zero() {
+        eprintln!(
+            "throughput: {:.1} readings/s",
+            (readings - 1) as f64 / elapsed.as_secs_f64()
+        );
+    }
+    if let Some(s) = LatencySummary::from_samples(latencies) {
+        eprintln!(
+            "sign-publish-verify latency ms: min {:.3} mean {:.3} p50 {:.3} p95 {:.3} p99 {:.3} max {:.3}",
+            ms(s.min), ms(s.mean), ms(s.p50), ms(s.p95), ms(s.p99), ms(s.max)
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use didkemb::cli::Args;
+use didkemb::issuer::Issuer;
+use didkemb::mqtt::{self, SensorPublisher, SensorReading};
+use didkemb::template::CredentialTemplate;
+use didkemb::DIDExampleStatic;
+
+// mqtt-sensor --broker ADDR [--topic sensors/readings] [--readings 100] [--interval-ms 1000]
+//             [--sensor urn:uuid:...] [--templates templates/ --template NOME]
+// Simula un sensore di temperatura: ogni lettura diventa una credenziale firmata.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    didkemb::runtime::block_on(run())?
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::from_env();
+    let broker: SocketAddr = match args.value("broker") {
+        Some(addr) => addr.parse()?,
+        None => return Err(didkemb::Error::Usage("--broker is required".to_string()).into()),
+    };
+    let topic = args.value("topic").unwrap_or(mqtt::DEFAULT_TOPIC);
+    let readings: u64 = args.parse_or("readings", 100)?;
+    let interval = Duration::from_millis(args.parse_or("interval-ms", 1000)?);
+    let sensor = args
+        .value("sensor")
+        .map(str::to_string)
+        .unwrap_or_else(|| "urn:uuid:".to_string() + &uuid::Uuid::new_v4().to_string());
+
+    let mut issuer = Issuer::new(
+        didkemb::issuer_key()?,
+        didkemb::ISSUER_DID,
+        didkemb::VERIFICATION_METHOD,
+        Arc::new(DIDExampleStatic),
+    );
+    if let Some(dir) = args.value("templates") {
+        issuer = issuer.with_templates(CredentialTemplate::load_dir(dir)?);
+    }
+    let mut publisher = SensorPublisher::connect(
+        mqtt::options(format!("sensor-{}", std::process::id()), broker),
+        issuer,
+        topic,
+    );
+    if let Some(template) = args.value("template") {
+        publisher = publisher.with_template(template);
+    }
+
+    for sequence in 0..readings {
+        let value = 20.0 + (sequence as f64 / 10.0).sin();
+        let reading = SensorReading::now(sensor.as_str(), sequence, value, "celsius");
+        let jwt = publisher.publish(&reading).await?;
+        eprintln!("{} {} bytes", sequence, jwt.len());
+        tokio::time::sleep(interval).await;
+    }
+    publisher.disconnect().await?;
+    Ok(())
+}
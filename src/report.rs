@@ -0,0 +1,693 @@
+//! Structured result of verifying a JWT credential: one entry per check, with
+//! the reason a check failed, serializable to JSON for gateway logs.
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ssi::jsonld::ContextLoader;
+use ssi::jws::DecodedJWS;
+use ssi::vc::{
+    Credential, CredentialOrJWT, JWTClaims, LinkedDataProofOptions, Presentation, ProofPurpose, URI,
+};
+use ssi_dids::did_resolve::{DIDResolver, Metadata, ResolutionInputMetadata};
+
+use crate::datamodel::{self, DataModel};
+use crate::digest::PayloadDigest;
+use crate::merkle::{BatchRoot, InclusionProof};
+use crate::resolver::versioned::{AsOf, VERSION_ID};
+use crate::schema::SchemaRegistry;
+use crate::trust::TrustRegistry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckKind {
+    /// JWS structure, JWT claims and VC data model.
+    Format,
+    IssuerResolution,
+    /// The issuer's DID document metadata does not say `deactivated: true`.
+    IssuerActive,
+    VerificationRelationship,
+    Signature,
+    /// `issuanceDate` not in the future, `expirationDate` not in the past.
+    Dates,
+    Status,
+    /// A payload supplied alongside the credential matches its `hash` claim.
+    Payload,
+    /// A record and its inclusion proof lead to the attested Merkle root.
+    Inclusion,
+    /// Each credential subject matches the JSON Schemas in `credentialSchema`.
+    Schema,
+    /// A trust registry rule accepts the issuer for the credential's types
+    /// and claims.
+    Trust,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Outcome {
+    Passed,
+    Failed,
+    /// Not run, because an earlier check failed or there was nothing to check.
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckResult {
+    pub check: CheckKind,
+    pub outcome: Outcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Kind {
+    #[default]
+    Credential,
+    Presentation,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationReport {
+    pub verified: bool,
+    #[serde(default)]
+    pub kind: Kind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub holder: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification_method: Option<String>,
+    /// `versionId` of the issuer's DID document the credential was checked
+    /// against, when the resolver keeps versions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_version: Option<String>,
+    /// Trust registry rule that accepted or rejected the credential.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trust_rule: Option<String>,
+    pub checks: Vec<CheckResult>,
+    /// Reports for the credentials embedded in a presentation.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub credentials: Vec<VerificationReport>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl VerificationReport {
+    fn pass(&mut self, check: CheckKind) {
+        self.push(check, Outcome::Passed, None);
+    }
+
+    fn fail(&mut self, check: CheckKind, reason: impl Into<String>) {
+        self.push(check, Outcome::Failed, Some(reason.into()));
+    }
+
+    fn skip(&mut self, check: CheckKind, reason: impl Into<String>) {
+        self.push(check, Outcome::Skipped, Some(reason.into()));
+    }
+
+    fn push(&mut self, check: CheckKind, outcome: Outcome, reason: Option<String>) {
+        self.checks.push(CheckResult {
+            check,
+            outcome,
+            reason,
+        });
+    }
+
+    /// Marks every check after the failed one as skipped and closes the report.
+    fn abort(mut self, from: CheckKind) -> Self {
+        for check in CHECKS.iter().skip_while(|c| **c != from).skip(1) {
+            self.skip(*check, format!("{:?} failed", from));
+        }
+        self.verified = false;
+        self
+    }
+
+    pub fn get(&self, check: CheckKind) -> Option<&CheckResult> {
+        self.checks.iter().find(|c| c.check == check)
+    }
+
+    /// `Ok(self)` if verified, `Error::Verification` otherwise.
+    pub fn into_result(self) -> crate::Result<Self> {
+        if self.verified {
+            Ok(self)
+        } else {
+            Err(crate::Error::Verification(Box::new(self)))
+        }
+    }
+
+    /// First failed check, if any.
+    pub fn failure(&self) -> Option<&CheckResult> {
+        self.checks.iter().find(|c| c.outcome == Outcome::Failed)
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.failure() {
+            None => write!(f, "verified"),
+            Some(CheckResult {
+                check,
+                reason: Some(reason),
+                ..
+            }) => write!(f, "{:?} check failed: {}", check, reason),
+            Some(CheckResult { check, .. }) => write!(f, "{:?} check failed", check),
+        }
+    }
+}
+
+const CHECKS: [CheckKind; 7] = [
+    CheckKind::Format,
+    CheckKind::IssuerResolution,
+    CheckKind::IssuerActive,
+    CheckKind::VerificationRelationship,
+    CheckKind::Signature,
+    CheckKind::Dates,
+    CheckKind::Status,
+];
+
+/// Verifies a JWT credential like `Credential::verify_jwt`, but records every
+/// check instead of a flat list of error strings.
+pub async fn verify_jwt(
+    jwt: &str,
+    options: Option<LinkedDataProofOptions>,
+    resolver: &dyn DIDResolver,
+    context_loader: &mut ContextLoader,
+) -> VerificationReport {
+    let options = options.unwrap_or_default();
+    let mut report = VerificationReport::default();
+
+    // Format
+    let (header_b64, payload_b64, signature_b64) = match ssi::jws::split_jws(jwt) {
+        Ok(parts) => parts,
+        Err(e) => {
+            report.fail(CheckKind::Format, format!("Unable to split JWS: {}", e));
+            return report.abort(CheckKind::Format);
+        }
+    };
+    let jws = match ssi::jws::decode_jws_parts(header_b64, payload_b64.as_bytes(), signature_b64) {
+        Ok(jws) => jws,
+        Err(e) => {
+            report.fail(CheckKind::Format, format!("Unable to decode JWS: {}", e));
+            return report.abort(CheckKind::Format);
+        }
+    };
+    let vc = match credential_of(&jws) {
+        Ok(vc) => vc,
+        Err(e) => {
+            report.fail(CheckKind::Format, format!("Invalid VC: {}", e));
+            return report.abort(CheckKind::Format);
+        }
+    };
+    let kid = match jws.header.key_id.clone() {
+        Some(kid) => kid,
+        None => {
+            report.fail(CheckKind::Format, "JWT header missing key id");
+            return report.abort(CheckKind::Format);
+        }
+    };
+    report.pass(CheckKind::Format);
+    report.verification_method = Some(kid.clone());
+
+    // Issuer resolution, against the documents in effect at issuance so that
+    // credentials signed before a key rotation still verify.
+    let issuer = match vc.issuer {
+        Some(ref issuer) => issuer.get_id(),
+        None => unreachable!("validate_unsigned checks the issuer"),
+    };
+    report.issuer = Some(issuer.clone());
+    let as_of = vc
+        .issuance_date
+        .clone()
+        .map(|issued| AsOf::new(resolver, issued.into()));
+    let issuer_resolver: &dyn DIDResolver = match as_of {
+        Some(ref as_of) => as_of,
+        None => resolver,
+    };
+    let (res_meta, doc, doc_meta) = issuer_resolver
+        .resolve(&issuer, &ResolutionInputMetadata::default())
+        .await;
+    let deactivated = doc_meta.as_ref().and_then(|meta| meta.deactivated) == Some(true);
+    report.document_version = doc_meta
+        .and_then(|meta| meta.property_set)
+        .and_then(|mut properties| properties.remove(VERSION_ID))
+        .and_then(|version| match version {
+            Metadata::String(version) => Some(version),
+            _ => None,
+        });
+    match (res_meta.error, doc) {
+        (None, Some(_)) => report.pass(CheckKind::IssuerResolution),
+        // A deactivated DID may resolve to metadata alone.
+        (None, None) if deactivated => report.pass(CheckKind::IssuerResolution),
+        (Some(e), _) => {
+            report.fail(CheckKind::IssuerResolution, e);
+            return report.abort(CheckKind::IssuerResolution);
+        }
+        (None, None) => {
+            report.fail(CheckKind::IssuerResolution, "Missing DID document");
+            return report.abort(CheckKind::IssuerResolution);
+        }
+    }
+
+    // Issuer active, now rather than at issuance: a deactivated DID must not
+    // be able to backdate new credentials into its active period.
+    let active = match deactivated {
+        true => Err(format!("{} is deactivated", issuer)),
+        false if as_of.is_some() => check_active(&issuer, resolver).await,
+        false => Ok(()),
+    };
+    if let Err(e) = active {
+        report.fail(CheckKind::IssuerActive, e);
+        return report.abort(CheckKind::IssuerActive);
+    }
+    report.pass(CheckKind::IssuerActive);
+
+    // Verification relationship
+    let purpose = options
+        .proof_purpose
+        .clone()
+        .unwrap_or(ProofPurpose::AssertionMethod);
+    if let Err(e) = check_relationship(
+        &issuer,
+        &kid,
+        &purpose,
+        options.verification_method.as_ref(),
+        issuer_resolver,
+    )
+    .await
+    {
+        report.fail(CheckKind::VerificationRelationship, e);
+        return report.abort(CheckKind::VerificationRelationship);
+    }
+    report.pass(CheckKind::VerificationRelationship);
+
+    // Signature
+    let key = match ssi_dids::did_resolve::resolve_key(&kid, issuer_resolver).await {
+        Ok(key) => key,
+        Err(e) => {
+            report.fail(
+                CheckKind::Signature,
+                format!("Unable to resolve key for JWS: {}", e),
+            );
+            return report.abort(CheckKind::Signature);
+        }
+    };
+    match ssi::jws::verify_bytes_warnable(
+        jws.header.algorithm,
+        &jws.signing_input,
+        &key,
+        &jws.signature,
+    ) {
+        Ok(mut warnings) => {
+            report.pass(CheckKind::Signature);
+            report.warnings.append(&mut warnings);
+        }
+        Err(e) => {
+            report.fail(
+                CheckKind::Signature,
+                format!("Unable to verify signature: {}", e),
+            );
+            return report.abort(CheckKind::Signature);
+        }
+    }
+
+    // Dates
+    match check_dates(&vc, Utc::now()) {
+        Ok(()) => report.pass(CheckKind::Dates),
+        Err(e) => report.fail(CheckKind::Dates, e),
+    }
+
+    // Status
+    if vc.credential_status.is_some() {
+        let result = vc.check_status(resolver, context_loader).await;
+        if result.errors.is_empty() {
+            report.pass(CheckKind::Status);
+        } else {
+            report.fail(CheckKind::Status, result.errors.join("; "));
+        }
+    } else {
+        report.skip(CheckKind::Status, "No credentialStatus");
+    }
+
+    report.verified = report.failure().is_none();
+    report
+}
+
+/// The credential a JWS carries: its payload for an `application/vc+jwt`
+/// envelope (VC Data Model 2.0), its `vc` claim otherwise.
+fn credential_of(jws: &DecodedJWS) -> Result<Credential, String> {
+    if jws.header.type_.as_deref() == Some(datamodel::VC_JWT) {
+        let vc = serde_json::from_slice(&jws.payload).map_err(|e| e.to_string())?;
+        return datamodel::from_v2(&vc);
+    }
+    let claims: JWTClaims = serde_json::from_slice(&jws.payload).map_err(|e| e.to_string())?;
+    let vc = Credential::from_jwt_claims(claims).map_err(|e| e.to_string())?;
+    vc.validate_unsigned().map_err(|e| e.to_string())?;
+    Ok(vc)
+}
+
+/// Verifies a JWT credential, then hashes `payload` and compares it with the
+/// digest attested in `credentialSubject`.
+pub async fn verify_jwt_with_payload(
+    jwt: &str,
+    payload: impl std::io::Read,
+    options: Option<LinkedDataProofOptions>,
+    resolver: &dyn DIDResolver,
+    context_loader: &mut ContextLoader,
+) -> VerificationReport {
+    let mut report = verify_jwt(jwt, options, resolver, context_loader).await;
+    if !report.verified {
+        report.skip(CheckKind::Payload, "Credential not verified");
+        return report;
+    }
+    let attested = credential_subject(jwt)
+        .and_then(|subject| PayloadDigest::from_subject(&subject).map_err(|e| e.to_string()));
+    match attested.and_then(|digest| digest.check(payload)) {
+        Ok(()) => report.pass(CheckKind::Payload),
+        Err(e) => report.fail(CheckKind::Payload, e),
+    }
+    report.verified = report.failure().is_none();
+    report
+}
+
+/// Verifies a batch credential, then checks that `record` is included under
+/// its Merkle root according to `proof`.
+pub async fn verify_jwt_with_inclusion(
+    jwt: &str,
+    record: &[u8],
+    proof: &InclusionProof,
+    options: Option<LinkedDataProofOptions>,
+    resolver: &dyn DIDResolver,
+    context_loader: &mut ContextLoader,
+) -> VerificationReport {
+    let mut report = verify_jwt(jwt, options, resolver, context_loader).await;
+    if !report.verified {
+        report.skip(CheckKind::Inclusion, "Credential not verified");
+        return report;
+    }
+    let attested = credential_subject(jwt)
+        .and_then(|subject| BatchRoot::from_subject(&subject).map_err(|e| e.to_string()));
+    match attested.and_then(|root| root.check(record, proof)) {
+        Ok(()) => report.pass(CheckKind::Inclusion),
+        Err(e) => report.fail(CheckKind::Inclusion, e),
+    }
+    report.verified = report.failure().is_none();
+    report
+}
+
+/// Verifies a JWT credential or presentation, then evaluates every verified
+/// credential against `registry`.
+pub async fn verify_with_trust(
+    jwt: &str,
+    registry: &TrustRegistry,
+    options: Option<LinkedDataProofOptions>,
+    resolver: &dyn DIDResolver,
+    context_loader: &mut ContextLoader,
+) -> VerificationReport {
+    let mut report = verify_any_jwt(jwt, options, resolver, context_loader).await;
+    check_trust(&mut report, jwt, registry);
+    report
+}
+
+/// Verifies a JWT credential or presentation, then validates every verified
+/// credential against the schemas it names.
+pub async fn verify_with_schemas(
+    jwt: &str,
+    schemas: &SchemaRegistry,
+    options: Option<LinkedDataProofOptions>,
+    resolver: &dyn DIDResolver,
+    context_loader: &mut ContextLoader,
+) -> VerificationReport {
+    let mut report = verify_any_jwt(jwt, options, resolver, context_loader).await;
+    check_schemas(&mut report, jwt, schemas);
+    report
+}
+
+/// Adds the `Trust` check to the report of `jwt`, or of each credential it
+/// presents.
+pub fn check_trust(report: &mut VerificationReport, jwt: &str, registry: &TrustRegistry) {
+    for_each_credential(report, jwt, CheckKind::Trust, |report, vc| {
+        let decision = registry.evaluate(vc);
+        report.trust_rule = decision.rule;
+        match decision.reason {
+            None => report.pass(CheckKind::Trust),
+            Some(reason) => report.fail(CheckKind::Trust, reason),
+        }
+    });
+}
+
+/// Adds the `Schema` check to the report of `jwt`, or of each credential it
+/// presents.
+pub fn check_schemas(report: &mut VerificationReport, jwt: &str, schemas: &SchemaRegistry) {
+    for_each_credential(report, jwt, CheckKind::Schema, |report, vc| {
+        if vc.credential_schema.is_none() {
+            return report.skip(CheckKind::Schema, "No credentialSchema");
+        }
+        match schemas.validate(vc) {
+            Ok(()) => report.pass(CheckKind::Schema),
+            Err(errors) => report.fail(CheckKind::Schema, crate::schema::describe(&errors)),
+        }
+    });
+}
+
+/// Runs `check` on the credential of a verified JWT credential, or on each
+/// verified credential of a JWT presentation; unverified ones skip it.
+fn for_each_credential(
+    report: &mut VerificationReport,
+    jwt: &str,
+    kind: CheckKind,
+    mut check: impl FnMut(&mut VerificationReport, &Credential),
+) {
+    let mut run = |report: &mut VerificationReport, vc: Result<Credential, String>| {
+        if !report.verified {
+            return report.skip(kind, "Credential not verified");
+        }
+        match vc {
+            Ok(vc) => check(report, &vc),
+            Err(e) => report.fail(kind, e),
+        }
+        report.verified = report.failure().is_none();
+    };
+    match report.kind {
+        Kind::Credential => run(report, datamodel::credential_unverified(jwt)),
+        Kind::Presentation => {
+            let credentials: Vec<CredentialOrJWT> = ssi::jws::decode_unverified(jwt)
+                .ok()
+                .and_then(|(_, payload)| serde_json::from_slice::<JWTClaims>(&payload).ok())
+                .and_then(|claims| Presentation::from_jwt_claims(claims).ok())
+                .map(|vp| vp.verifiable_credential.into_iter().flatten().collect())
+                .unwrap_or_default();
+            for (vc_report, vc) in report.credentials.iter_mut().zip(credentials) {
+                let vc = match vc {
+                    CredentialOrJWT::JWT(vc_jwt) => datamodel::credential_unverified(&vc_jwt),
+                    CredentialOrJWT::Credential(vc) => Ok(vc),
+                };
+                run(vc_report, vc);
+            }
+            report.verified =
+                report.failure().is_none() && report.credentials.iter().all(|vc| vc.verified);
+        }
+    }
+}
+
+/// `credentialSubject` of a JWT credential, read without verification.
+fn credential_subject(jwt: &str) -> Result<serde_json::Value, String> {
+    Ok(datamodel::decode_unverified(jwt)?["credentialSubject"].take())
+}
+
+/// Verifies a JWT presentation: the holder's proof, then every embedded
+/// credential, each reported under `credentials`.
+pub async fn verify_presentation_jwt(
+    jwt: &str,
+    options: Option<LinkedDataProofOptions>,
+    resolver: &dyn DIDResolver,
+    context_loader: &mut ContextLoader,
+) -> VerificationReport {
+    let mut report = VerificationReport {
+        kind: Kind::Presentation,
+        ..Default::default()
+    };
+    // The holder proves control with an authentication key, not an assertion one.
+    let options = options.unwrap_or_else(|| LinkedDataProofOptions {
+        proof_purpose: Some(ProofPurpose::Authentication),
+        ..Default::default()
+    });
+    let purpose = options
+        .proof_purpose
+        .clone()
+        .unwrap_or(ProofPurpose::Authentication);
+    let pinned = options.verification_method.clone();
+
+    // Checked before the proof, which ssi would otherwise only report as
+    // "No applicable JWS or proof".
+    if let Some((holder, kid)) = presentation_signer(jwt) {
+        report.holder = holder.clone();
+        report.verification_method = kid.clone();
+        let relationship = match (holder, kid) {
+            (Some(holder), Some(kid)) => {
+                check_relationship(&holder, &kid, &purpose, pinned.as_ref(), resolver).await
+            }
+            (None, _) => Err("Presentation has no holder".to_string()),
+            (_, None) => Err("JWT header missing key id".to_string()),
+        };
+        if let Err(e) = relationship {
+            report.pass(CheckKind::Format);
+            report.fail(CheckKind::VerificationRelationship, e);
+            report.skip(CheckKind::Signature, "VerificationRelationship failed");
+            report.verified = false;
+            return report;
+        }
+    }
+
+    let (vp, result) =
+        Presentation::decode_verify_jwt(jwt, Some(options), resolver, context_loader).await;
+    let vp = match vp {
+        Some(vp) => vp,
+        None => {
+            report.fail(CheckKind::Format, result.errors.join("; "));
+            report.verified = false;
+            return report;
+        }
+    };
+    report.pass(CheckKind::Format);
+    report.pass(CheckKind::VerificationRelationship);
+    if result.errors.is_empty() {
+        report.pass(CheckKind::Signature);
+    } else {
+        report.fail(CheckKind::Signature, result.errors.join("; "));
+    }
+    report.warnings.extend(result.warnings);
+
+    for vc in vp.verifiable_credential.into_iter().flatten() {
+        let vc_report = match vc {
+            CredentialOrJWT::JWT(vc_jwt) => {
+                verify_jwt(&vc_jwt, None, resolver, context_loader).await
+            }
+            CredentialOrJWT::Credential(vc) => verify_ldp(&vc, resolver, context_loader).await,
+        };
+        report.credentials.push(vc_report);
+    }
+
+    report.verified = report.failure().is_none() && report.credentials.iter().all(|vc| vc.verified);
+    report
+}
+
+/// Verifies a credential carrying a Linked Data proof. Its contexts come from
+/// `context_loader`; pass [`crate::context::loader`] for the IoT context.
+pub async fn verify_ldp(
+    vc: &Credential,
+    resolver: &dyn DIDResolver,
+    context_loader: &mut ContextLoader,
+) -> VerificationReport {
+    let mut report = VerificationReport {
+        issuer: vc.issuer.as_ref().map(|i| i.get_id()),
+        ..Default::default()
+    };
+    if let Some(ref issuer) = report.issuer {
+        match check_active(issuer, resolver).await {
+            Ok(()) => report.pass(CheckKind::IssuerActive),
+            Err(e) => report.fail(CheckKind::IssuerActive, e),
+        }
+    }
+    let result = vc.verify(None, resolver, context_loader).await;
+    if result.errors.is_empty() {
+        report.pass(CheckKind::Signature);
+    } else {
+        report.fail(CheckKind::Signature, result.errors.join("; "));
+    }
+    match check_dates(vc, Utc::now()) {
+        Ok(()) => report.pass(CheckKind::Dates),
+        Err(e) => report.fail(CheckKind::Dates, e),
+    }
+    report.verified = report.failure().is_none();
+    report
+}
+
+/// Fails when `did`'s document metadata says `deactivated: true`.
+async fn check_active(did: &str, resolver: &dyn DIDResolver) -> Result<(), String> {
+    let (_, _, doc_meta) = resolver
+        .resolve(did, &ResolutionInputMetadata::default())
+        .await;
+    match doc_meta.and_then(|meta| meta.deactivated) {
+        Some(true) => Err(format!("{} is deactivated", did)),
+        _ => Ok(()),
+    }
+}
+
+/// Verifies a JWT credential or presentation, depending on whether its
+/// claims carry `vc` or `vp`.
+pub async fn verify_any_jwt(
+    jwt: &str,
+    options: Option<LinkedDataProofOptions>,
+    resolver: &dyn DIDResolver,
+    context_loader: &mut ContextLoader,
+) -> VerificationReport {
+    let is_presentation = ssi::jws::decode_unverified(jwt)
+        .ok()
+        .and_then(|(_, payload)| serde_json::from_slice::<serde_json::Value>(&payload).ok())
+        .is_some_and(|claims| claims.get("vp").is_some());
+    if is_presentation {
+        verify_presentation_jwt(jwt, options, resolver, context_loader).await
+    } else {
+        verify_jwt(jwt, options, resolver, context_loader).await
+    }
+}
+
+/// Holder and `kid` of a JWT presentation, read without verification; `None`
+/// if it does not decode as one.
+fn presentation_signer(jwt: &str) -> Option<(Option<String>, Option<String>)> {
+    let (header, payload) = ssi::jws::decode_unverified(jwt).ok()?;
+    let claims: JWTClaims = serde_json::from_slice(&payload).ok()?;
+    let vp = Presentation::from_jwt_claims(claims).ok()?;
+    vp.validate_unsigned().ok()?;
+    Some((vp.holder.map(|h| h.to_string()), header.key_id))
+}
+
+/// Checks that `did`'s document lists `kid` under the relationship for
+/// `purpose` and, if the caller pinned a verification method, that `kid` is
+/// that method. A pinned method is never trusted on its own.
+async fn check_relationship(
+    did: &str,
+    kid: &str,
+    purpose: &ProofPurpose,
+    pinned: Option<&URI>,
+    resolver: &dyn DIDResolver,
+) -> Result<(), String> {
+    if let Some(pinned) = pinned {
+        if pinned.as_str() != kid {
+            return Err(format!("{} is not the expected {}", kid, pinned));
+        }
+    }
+    let allowed =
+        ssi::vc::get_verification_methods_for_purpose(did, resolver, purpose.clone()).await?;
+    if allowed.iter().any(|vm| vm == kid) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} is not authorized for {:?} by {}",
+            kid, purpose, did
+        ))
+    }
+}
+
+fn check_dates(vc: &Credential, now: DateTime<Utc>) -> Result<(), String> {
+    if let Some(ref issuance) = vc.issuance_date {
+        let issuance: DateTime<Utc> = issuance.clone().into();
+        if issuance > now {
+            let name = match DataModel::of(vc) {
+                DataModel::V1 => "issuanceDate",
+                DataModel::V2 => "validFrom",
+            };
+            return Err(format!("{} {} is in the future", name, issuance));
+        }
+    }
+    if let Some(ref expiration) = vc.expiration_date {
+        let expiration: DateTime<Utc> = expiration.clone().into();
+        if expiration < now {
+            return Err(format!("Credential expired at {}", expiration));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,137 @@
+pub mod cache;
+pub mod chain;
+pub mod file;
+pub mod jwk;
+pub mod peer;
+pub mod versioned;
+pub mod web;
+
+pub use cache::{CacheStats, CachingResolver};
+pub use chain::ResolverChain;
+pub use file::FileResolver;
+pub use jwk::DIDJWK;
+pub use peer::DIDPeer;
+pub use versioned::{AsOf, DocumentHistory, VersionedResolver};
+pub use web::DIDWeb;
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use ssi_dids::did_resolve::{
+    DIDResolver, DocumentMetadata, ResolutionInputMetadata, ResolutionMetadata, ERROR_NOT_FOUND,
+};
+use ssi_dids::Document;
+
+use crate::ISSUER_DID;
+
+/// The pre-provisioned `did-example-mine.json` document.
+fn pre_provisioned() -> Result<Document, String> {
+    Document::from_json(include_str!("../did-example-mine.json"))
+        .map_err(|e| format!("Unable to parse DID document: {:?}", e))
+}
+
+/// Resolver serving the pre-provisioned `did-example-mine.json` document.
+pub struct DIDExampleStatic;
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl DIDResolver for DIDExampleStatic {
+    async fn resolve(
+        &self,
+        did: &str,
+        _input_metadata: &ResolutionInputMetadata,
+    ) -> (
+        ResolutionMetadata,
+        Option<Document>,
+        Option<DocumentMetadata>,
+    ) {
+        match did {
+            ISSUER_DID => match pre_provisioned() {
+                Ok(doc) => (
+                    ResolutionMetadata::default(),
+                    Some(doc),
+                    Some(DocumentMetadata::default()),
+                ),
+                Err(e) => (ResolutionMetadata::from_error(&e), None, None),
+            },
+            _ => (ResolutionMetadata::from_error(ERROR_NOT_FOUND), None, None),
+        }
+    }
+}
+
+/// In-memory documents keyed by DID. A DID can be deactivated while the
+/// resolver is in use, to decommission a compromised device: it still
+/// resolves, with `deactivated: true` in the document metadata.
+#[derive(Default)]
+pub struct StaticResolver {
+    documents: RwLock<HashMap<String, (Document, bool)>>,
+}
+
+impl StaticResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serves what [`DIDExampleStatic`] does.
+    pub fn pre_provisioned() -> Self {
+        let resolver = Self::new();
+        resolver.insert(pre_provisioned().expect("did-example-mine.json is valid"));
+        resolver
+    }
+
+    pub fn insert(&self, document: Document) {
+        self.documents
+            .write()
+            .unwrap()
+            .insert(document.id.clone(), (document, false));
+    }
+
+    /// Marks `did` as deactivated; `false` if it is unknown.
+    pub fn deactivate(&self, did: &str) -> bool {
+        match self.documents.write().unwrap().get_mut(did) {
+            Some((_, deactivated)) => {
+                *deactivated = true;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl DIDResolver for StaticResolver {
+    async fn resolve(
+        &self,
+        did: &str,
+        _input_metadata: &ResolutionInputMetadata,
+    ) -> (
+        ResolutionMetadata,
+        Option<Document>,
+        Option<DocumentMetadata>,
+    ) {
+        match self.documents.read().unwrap().get(did) {
+            Some((doc, deactivated)) => (
+                ResolutionMetadata::default(),
+                Some(doc.clone()),
+                Some(DocumentMetadata {
+                    deactivated: deactivated.then_some(true),
+                    ..Default::default()
+                }),
+            ),
+            None => (ResolutionMetadata::from_error(ERROR_NOT_FOUND), None, None),
+        }
+    }
+}
+
+/// did:key, did:jwk and did:peer resolved from the identifier itself, did:web
+/// over HTTPS, then the pre-provisioned document.
+pub fn default_chain() -> ResolverChain {
+    ResolverChain::new()
+        .route("key", did_method_key::DIDKey)
+        .route("jwk", DIDJWK)
+        .route("peer", DIDPeer)
+        .route("web", DIDWeb::new())
+        .fallback(DIDExampleStatic)
+}
@@ -0,0 +1,260 @@
+//! Caching wrapper for any [`DIDResolver`].
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use ssi_dids::did_resolve::{
+    Content, ContentMetadata, DIDResolver, DereferencingInputMetadata, DereferencingMetadata,
+    DocumentMetadata, Metadata, ResolutionInputMetadata, ResolutionMetadata,
+};
+use ssi_dids::{DIDMethod, Document, PrimaryDIDURL};
+
+use super::versioned::{NEXT_UPDATE, VERSION_ID};
+
+/// Counters of a [`CachingResolver`], serializable for `/metrics`-style output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Entries dropped to stay within the size bound; expired entries that
+    /// are refreshed are counted as misses, not evictions.
+    pub evictions: u64,
+    /// Time spent in the wrapped resolver, over all misses.
+    pub resolve_time: Duration,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+
+    /// Mean latency of the wrapped resolver.
+    pub fn mean_resolve_time(&self) -> Duration {
+        match self.misses {
+            0 => Duration::ZERO,
+            n => self.resolve_time / n as u32,
+        }
+    }
+}
+
+struct Entry {
+    resolution: ResolutionMetadata,
+    document: Document,
+    metadata: Option<DocumentMetadata>,
+    inserted: Instant,
+    last_used: Instant,
+}
+
+/// Keeps successful resolutions of the wrapped resolver for `ttl`, holding at
+/// most `capacity` documents and evicting the least recently used. Hits return
+/// the stored resolution and document metadata. Errors and deactivated DIDs
+/// are never cached, and `noCache: true` in the input metadata bypasses the
+/// cache.
+pub struct CachingResolver<R> {
+    inner: R,
+    ttl: Duration,
+    capacity: usize,
+    entries: Mutex<HashMap<String, Entry>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    resolve_nanos: AtomicU64,
+}
+
+impl<R: DIDResolver> CachingResolver<R> {
+    pub fn new(inner: R, ttl: Duration, capacity: usize) -> Self {
+        CachingResolver {
+            inner,
+            ttl,
+            capacity: capacity.max(1),
+            entries: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+            resolve_nanos: AtomicU64::new(0),
+        }
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            resolve_time: Duration::from_nanos(self.resolve_nanos.load(Ordering::Relaxed)),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// The DID alone for plain resolutions; requests naming a `versionId` or
+    /// otherwise qualified are cached separately. `versionTime` is left out:
+    /// whether an entry answers it depends on the version it holds (see
+    /// [`covers`]), not on the exact time asked for.
+    fn key(did: &str, input: &ResolutionInputMetadata) -> String {
+        let qualified = ResolutionInputMetadata {
+            no_cache: None,
+            version_time: None,
+            ..input.clone()
+        };
+        match serde_json::to_string(&qualified) {
+            Ok(options) if options != "{}" => format!("{} {}", did, options),
+            _ => did.to_string(),
+        }
+    }
+
+    fn lookup(
+        &self,
+        key: &str,
+        input: &ResolutionInputMetadata,
+    ) -> Option<(ResolutionMetadata, Document, Option<DocumentMetadata>)> {
+        let mut entries = self.entries.lock().unwrap();
+        let now = Instant::now();
+        match entries.get_mut(key) {
+            Some(entry) if now.duration_since(entry.inserted) >= self.ttl => {
+                entries.remove(key);
+                None
+            }
+            Some(entry) if covers(&entry.metadata, input) => {
+                entry.last_used = now;
+                Some((
+                    entry.resolution.clone(),
+                    entry.document.clone(),
+                    entry.metadata.clone(),
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    fn store(
+        &self,
+        key: String,
+        resolution: &ResolutionMetadata,
+        document: &Document,
+        metadata: &Option<DocumentMetadata>,
+    ) {
+        let mut entries = self.entries.lock().unwrap();
+        if !entries.contains_key(&key) && entries.len() >= self.capacity {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        let now = Instant::now();
+        entries.insert(
+            key,
+            Entry {
+                resolution: resolution.clone(),
+                document: document.clone(),
+                metadata: metadata.clone(),
+                inserted: now,
+                last_used: now,
+            },
+        );
+    }
+}
+
+/// Whether a document resolved with `metadata` answers `input`. Documents
+/// without a `versionId` answer any `versionTime`; a version answers the
+/// times from its `updated` to its `nextUpdate`, and plain requests only
+/// when no newer version is known.
+fn covers(metadata: &Option<DocumentMetadata>, input: &ResolutionInputMetadata) -> bool {
+    let Some(properties) = metadata.as_ref().and_then(|m| m.property_set.as_ref()) else {
+        return true;
+    };
+    if input.version_id.is_some() || !properties.contains_key(VERSION_ID) {
+        return true;
+    }
+    let next_update = match properties.get(NEXT_UPDATE) {
+        Some(Metadata::String(next)) => match DateTime::parse_from_rfc3339(next) {
+            Ok(next) => Some(next.with_timezone(&Utc)),
+            Err(_) => return false,
+        },
+        _ => None,
+    };
+    let Some(ref time) = input.version_time else {
+        return next_update.is_none();
+    };
+    let Ok(time) = DateTime::parse_from_rfc3339(time) else {
+        return false;
+    };
+    let updated = metadata.as_ref().and_then(|m| m.updated);
+    updated.is_some_and(|updated| updated <= time) && next_update.is_none_or(|next| time < next)
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<R: DIDResolver> DIDResolver for CachingResolver<R> {
+    async fn resolve(
+        &self,
+        did: &str,
+        input_metadata: &ResolutionInputMetadata,
+    ) -> (
+        ResolutionMetadata,
+        Option<Document>,
+        Option<DocumentMetadata>,
+    ) {
+        let key = Self::key(did, input_metadata);
+        let use_cache = input_metadata.no_cache != Some(true);
+        if use_cache {
+            if let Some((resolution, document, metadata)) = self.lookup(&key, input_metadata) {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return (resolution, Some(document), metadata);
+            }
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let start = Instant::now();
+        let (res_meta, document, doc_meta) = self.inner.resolve(did, input_metadata).await;
+        self.resolve_nanos
+            .fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        // A deactivation must be seen as soon as it is published.
+        let deactivated = doc_meta.as_ref().and_then(|m| m.deactivated) == Some(true);
+        if let (None, Some(ref document)) = (&res_meta.error, &document) {
+            if use_cache && !deactivated {
+                self.store(key, &res_meta, document, &doc_meta);
+            }
+        }
+        (res_meta, document, doc_meta)
+    }
+
+    async fn dereference(
+        &self,
+        primary_did_url: &PrimaryDIDURL,
+        input_metadata: &DereferencingInputMetadata,
+    ) -> Option<(DereferencingMetadata, Content, ContentMetadata)> {
+        self.inner
+            .dereference(primary_did_url, input_metadata)
+            .await
+    }
+
+    fn to_did_method(&self) -> Option<&dyn DIDMethod> {
+        self.inner.to_did_method()
+    }
+}
@@ -0,0 +1,155 @@
+//! Resolver combinator: routes DIDs to resolvers by method, then falls back
+//! through an ordered list until one returns a document.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use ssi_dids::did_resolve::{
+    DIDResolver, DocumentMetadata, Metadata, ResolutionInputMetadata, ResolutionMetadata,
+    ERROR_INVALID_DID, ERROR_NOT_FOUND,
+};
+use ssi_dids::Document;
+
+/// Key of the list of failed attempts in the resolution metadata.
+pub const ATTEMPTS: &str = "attempts";
+
+struct Link {
+    name: String,
+    /// `None` for fallbacks, which are tried for every DID.
+    method: Option<String>,
+    resolver: Box<dyn DIDResolver + Send>,
+}
+
+impl Link {
+    fn handles(&self, did: &str) -> bool {
+        match self.method {
+            Some(ref method) => did
+                .strip_prefix("did:")
+                .and_then(|rest| rest.strip_prefix(method.as_str()))
+                .is_some_and(|rest| rest.starts_with(':')),
+            None => true,
+        }
+    }
+}
+
+fn short_type_name<R>() -> String {
+    let name = std::any::type_name::<R>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name).to_string()
+}
+
+/// Tries, in order, every resolver routed to the DID's method and then every
+/// fallback. The first document wins, as does a report that the DID is
+/// deactivated, even without a document; if neither is found, the resolution
+/// metadata carries each attempt's error under [`ATTEMPTS`], and `error` is
+/// `notFound` unless some resolver reported something else.
+#[derive(Default)]
+pub struct ResolverChain {
+    links: Vec<Link>,
+}
+
+impl ResolverChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Routes `did:<method>:...` to `resolver`.
+    pub fn route<R: DIDResolver + Send + 'static>(mut self, method: &str, resolver: R) -> Self {
+        self.links.push(Link {
+            name: format!("{} (did:{})", short_type_name::<R>(), method),
+            method: Some(method.to_string()),
+            resolver: Box::new(resolver),
+        });
+        self
+    }
+
+    /// Tries `resolver` for any DID the routed resolvers did not resolve.
+    pub fn fallback<R: DIDResolver + Send + 'static>(mut self, resolver: R) -> Self {
+        self.links.push(Link {
+            name: short_type_name::<R>(),
+            method: None,
+            resolver: Box::new(resolver),
+        });
+        self
+    }
+
+    /// Routed resolvers first, fallbacks after, each in insertion order.
+    fn candidates<'a>(&'a self, did: &'a str) -> impl Iterator<Item = &'a Link> + 'a {
+        let routed = self
+            .links
+            .iter()
+            .filter(move |l| l.method.is_some() && l.handles(did));
+        let fallbacks = self.links.iter().filter(|l| l.method.is_none());
+        routed.chain(fallbacks)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl DIDResolver for ResolverChain {
+    async fn resolve(
+        &self,
+        did: &str,
+        input_metadata: &ResolutionInputMetadata,
+    ) -> (
+        ResolutionMetadata,
+        Option<Document>,
+        Option<DocumentMetadata>,
+    ) {
+        if !did.starts_with("did:") {
+            return (
+                ResolutionMetadata::from_error(ERROR_INVALID_DID),
+                None,
+                None,
+            );
+        }
+        let mut attempts = Vec::new();
+        let mut error = ERROR_NOT_FOUND.to_string();
+        for link in self.candidates(did) {
+            let (res_meta, doc, doc_meta) = link.resolver.resolve(did, input_metadata).await;
+            match (res_meta.error, doc) {
+                (None, Some(doc)) => {
+                    return (
+                        ResolutionMetadata {
+                            error: None,
+                            ..res_meta
+                        },
+                        Some(doc),
+                        doc_meta,
+                    );
+                }
+                // A deactivated DID may resolve to metadata alone; later
+                // resolvers must not bring it back to life.
+                (None, None) if doc_meta.as_ref().and_then(|m| m.deactivated) == Some(true) => {
+                    return (
+                        ResolutionMetadata {
+                            error: None,
+                            ..res_meta
+                        },
+                        None,
+                        doc_meta,
+                    );
+                }
+                (e, _) => {
+                    let e = e.unwrap_or_else(|| "No document".to_string());
+                    if e != ERROR_NOT_FOUND && error == ERROR_NOT_FOUND {
+                        error = e.clone();
+                    }
+                    attempts.push(Metadata::Map(HashMap::from([
+                        ("resolver".to_string(), Metadata::String(link.name.clone())),
+                        ("error".to_string(), Metadata::String(e)),
+                    ])));
+                }
+            }
+        }
+        let res_meta = ResolutionMetadata {
+            error: Some(error),
+            content_type: None,
+            property_set: Some(HashMap::from([(
+                ATTEMPTS.to_string(),
+                Metadata::List(attempts),
+            )])),
+        };
+        (res_meta, None, None)
+    }
+}
@@ -0,0 +1,99 @@
+//! Resolver for DID documents kept as JSON files, such as those written by
+//! the `did-doc` tool. A file holds either a bare document or a resolution
+//! result, `{"didDocument": ..., "didDocumentMetadata": ...}`, whose metadata
+//! (such as `deactivated: true`) is returned with the document.
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use serde_json::Value;
+use ssi_dids::did_resolve::{
+    DIDResolver, DocumentMetadata, ResolutionInputMetadata, ResolutionMetadata, ERROR_NOT_FOUND,
+    TYPE_DID_LD_JSON,
+};
+use ssi_dids::Document;
+
+/// Serves every `*.json` document in a directory (or a single file), matched
+/// by `id`. Files are read on each resolution, so edits show up immediately;
+/// wrap it in a [`CachingResolver`](super::CachingResolver) when that is too
+/// slow.
+pub struct FileResolver {
+    path: PathBuf,
+}
+
+impl FileResolver {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileResolver { path: path.into() }
+    }
+
+    fn files(&self) -> std::io::Result<Vec<PathBuf>> {
+        if self.path.is_file() {
+            return Ok(vec![self.path.clone()]);
+        }
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(&self.path)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "json") {
+                files.push(path);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    fn read(path: &Path) -> Result<(Document, DocumentMetadata), String> {
+        let json = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let mut value: Value = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+        let Some(document) = value.get_mut("didDocument").map(Value::take) else {
+            let doc = serde_json::from_value(value).map_err(|e| e.to_string())?;
+            return Ok((doc, DocumentMetadata::default()));
+        };
+        let metadata = match value.get_mut("didDocumentMetadata").map(Value::take) {
+            Some(metadata) => serde_json::from_value(metadata).map_err(|e| e.to_string())?,
+            None => DocumentMetadata::default(),
+        };
+        let doc = serde_json::from_value(document).map_err(|e| e.to_string())?;
+        Ok((doc, metadata))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl DIDResolver for FileResolver {
+    async fn resolve(
+        &self,
+        did: &str,
+        _input_metadata: &ResolutionInputMetadata,
+    ) -> (
+        ResolutionMetadata,
+        Option<Document>,
+        Option<DocumentMetadata>,
+    ) {
+        let files = match self.files() {
+            Ok(files) => files,
+            Err(e) => {
+                return (
+                    ResolutionMetadata::from_error(&format!("{}: {}", self.path.display(), e)),
+                    None,
+                    None,
+                )
+            }
+        };
+        // Unreadable files are skipped: one bad document must not hide the others.
+        let found = files
+            .iter()
+            .filter_map(|path| Self::read(path).ok())
+            .find(|(doc, _)| doc.id == did);
+        match found {
+            Some((doc, metadata)) => (
+                ResolutionMetadata {
+                    content_type: Some(TYPE_DID_LD_JSON.to_string()),
+                    ..Default::default()
+                },
+                Some(doc),
+                Some(metadata),
+            ),
+            None => (ResolutionMetadata::from_error(ERROR_NOT_FOUND), None, None),
+        }
+    }
+}
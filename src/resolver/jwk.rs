@@ -0,0 +1,126 @@
+//! did:jwk: the DID is the base64url-encoded public JWK, so generating one
+//! needs no registry and resolving it needs no network.
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde_json::json;
+use ssi::jwk::JWK;
+use ssi_dids::did_resolve::{
+    DIDResolver, DocumentMetadata, ResolutionInputMetadata, ResolutionMetadata, ERROR_INVALID_DID,
+    TYPE_DID_LD_JSON,
+};
+use ssi_dids::{DIDMethod, Document, Source};
+
+/// Context of the `JsonWebKey2020` verification methods.
+pub const JWS_2020_CONTEXT: &str = "https://w3id.org/security/suites/jws-2020/v1";
+
+/// Verification relationships of a key given its `use`: signing keys are not
+/// used for key agreement and encryption keys are used for nothing else.
+pub(crate) fn relationships(
+    key_use: Option<&str>,
+    vm: &str,
+) -> serde_json::Map<String, serde_json::Value> {
+    let signing = [
+        "authentication",
+        "assertionMethod",
+        "capabilityInvocation",
+        "capabilityDelegation",
+    ];
+    let names: Vec<&str> = match key_use {
+        Some("sig") => signing.to_vec(),
+        Some("enc") => vec!["keyAgreement"],
+        _ => signing.into_iter().chain(["keyAgreement"]).collect(),
+    };
+    names
+        .into_iter()
+        .map(|name| (name.to_string(), json!([vm])))
+        .collect()
+}
+
+pub struct DIDJWK;
+
+impl DIDJWK {
+    /// The verification method of a did:jwk DID, which has exactly one.
+    pub fn verification_method(did: &str) -> String {
+        format!("{}#0", did)
+    }
+
+    fn document(did: &str) -> Option<Document> {
+        let encoded = did.strip_prefix("did:jwk:")?;
+        let bytes = URL_SAFE_NO_PAD.decode(encoded).ok()?;
+        let jwk: JWK = serde_json::from_slice(&bytes).ok()?;
+        if jwk != jwk.to_public() {
+            return None;
+        }
+        let vm = Self::verification_method(did);
+        let mut doc = json!({
+            "@context": ["https://www.w3.org/ns/did/v1", JWS_2020_CONTEXT],
+            "id": did,
+            "verificationMethod": [{
+                "id": vm,
+                "type": "JsonWebKey2020",
+                "controller": did,
+                "publicKeyJwk": jwk,
+            }],
+        });
+        doc.as_object_mut()?
+            .extend(relationships(jwk.public_key_use.as_deref(), &vm));
+        serde_json::from_value(doc).ok()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl DIDResolver for DIDJWK {
+    async fn resolve(
+        &self,
+        did: &str,
+        _input_metadata: &ResolutionInputMetadata,
+    ) -> (
+        ResolutionMetadata,
+        Option<Document>,
+        Option<DocumentMetadata>,
+    ) {
+        match Self::document(did) {
+            Some(doc) => (
+                ResolutionMetadata {
+                    content_type: Some(TYPE_DID_LD_JSON.to_string()),
+                    ..Default::default()
+                },
+                Some(doc),
+                Some(DocumentMetadata::default()),
+            ),
+            None => (
+                ResolutionMetadata::from_error(ERROR_INVALID_DID),
+                None,
+                None,
+            ),
+        }
+    }
+
+    fn to_did_method(&self) -> Option<&dyn DIDMethod> {
+        Some(self)
+    }
+}
+
+impl DIDMethod for DIDJWK {
+    fn name(&self) -> &'static str {
+        "jwk"
+    }
+
+    /// did:jwk of the public part of the key.
+    fn generate(&self, source: &Source) -> Option<String> {
+        let jwk = match source {
+            Source::Key(jwk) => jwk,
+            Source::KeyAndPattern(jwk, "") => jwk,
+            _ => return None,
+        };
+        let json = serde_json::to_vec(&jwk.to_public()).ok()?;
+        Some(format!("did:jwk:{}", URL_SAFE_NO_PAD.encode(json)))
+    }
+
+    fn to_resolver(&self) -> &dyn DIDResolver {
+        self
+    }
+}
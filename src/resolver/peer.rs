@@ -0,0 +1,294 @@
+//! did:peer, numalgo 0 and 2: the keys (and, for numalgo 2, the services) are
+//! encoded in the DID itself, so peers can exchange DIDs without a registry.
+//!
+//! Keys are multibase (base58btc) multicodec values; Ed25519 keys sign and
+//! X25519 keys are used for key agreement.
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde_json::{json, Map, Value};
+use ssi::jwk::{Base64urlUInt, OctetParams, Params, JWK};
+use ssi_dids::did_resolve::{
+    DIDResolver, DocumentMetadata, ResolutionInputMetadata, ResolutionMetadata, ERROR_INVALID_DID,
+    TYPE_DID_LD_JSON,
+};
+use ssi_dids::{DIDMethod, Document, Source};
+
+use super::jwk::{relationships, JWS_2020_CONTEXT};
+
+const ED25519: [u8; 2] = [0xed, 0x01];
+const X25519: [u8; 2] = [0xec, 0x01];
+
+/// Service keys and values shortened in numalgo 2 DIDs.
+const ABBREVIATIONS: [(&str, &str); 5] = [
+    ("type", "t"),
+    ("serviceEndpoint", "s"),
+    ("routingKeys", "r"),
+    ("accept", "a"),
+    ("DIDCommMessaging", "dm"),
+];
+
+/// What a key of a numalgo 2 DID is for; the prefix of its element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Purpose {
+    /// `A`
+    Assertion,
+    /// `V`
+    Authentication,
+    /// `I`
+    CapabilityInvocation,
+    /// `D`
+    CapabilityDelegation,
+    /// `E`
+    KeyAgreement,
+}
+
+impl Purpose {
+    fn prefix(self) -> char {
+        match self {
+            Purpose::Assertion => 'A',
+            Purpose::Authentication => 'V',
+            Purpose::CapabilityInvocation => 'I',
+            Purpose::CapabilityDelegation => 'D',
+            Purpose::KeyAgreement => 'E',
+        }
+    }
+
+    fn from_prefix(prefix: char) -> Option<Self> {
+        [
+            Purpose::Assertion,
+            Purpose::Authentication,
+            Purpose::CapabilityInvocation,
+            Purpose::CapabilityDelegation,
+            Purpose::KeyAgreement,
+        ]
+        .into_iter()
+        .find(|p| p.prefix() == prefix)
+    }
+
+    fn relationship(self) -> &'static str {
+        match self {
+            Purpose::Assertion => "assertionMethod",
+            Purpose::Authentication => "authentication",
+            Purpose::CapabilityInvocation => "capabilityInvocation",
+            Purpose::CapabilityDelegation => "capabilityDelegation",
+            Purpose::KeyAgreement => "keyAgreement",
+        }
+    }
+}
+
+/// Multibase multicodec form of an Ed25519 or X25519 public key.
+pub fn encode_key(jwk: &JWK) -> Option<String> {
+    let Params::OKP(ref params) = jwk.params else {
+        return None;
+    };
+    let codec = match params.curve.as_str() {
+        "Ed25519" => ED25519,
+        "X25519" => X25519,
+        _ => return None,
+    };
+    Some(multibase::encode(
+        multibase::Base::Base58Btc,
+        [&codec[..], &params.public_key.0].concat(),
+    ))
+}
+
+fn decode_key(encoded: &str) -> Option<JWK> {
+    let (base, bytes) = multibase::decode(encoded).ok()?;
+    if base != multibase::Base::Base58Btc || bytes.len() != 34 {
+        return None;
+    }
+    let curve = match [bytes[0], bytes[1]] {
+        ED25519 => "Ed25519",
+        X25519 => "X25519",
+        _ => return None,
+    };
+    Some(JWK::from(Params::OKP(OctetParams {
+        curve: curve.to_string(),
+        public_key: Base64urlUInt(bytes[2..].to_vec()),
+        private_key: None,
+    })))
+}
+
+/// Replaces, recursively, every object key and string value found as the
+/// first of a pair with the second.
+fn rename(value: Value, pairs: &[(&str, &str)]) -> Value {
+    let swap = |s: String| match pairs.iter().find(|(from, _)| *from == s) {
+        Some((_, to)) => to.to_string(),
+        None => s,
+    };
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(k, v)| (swap(k), rename(v, pairs)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(|v| rename(v, pairs)).collect()),
+        Value::String(s) => Value::String(swap(s)),
+        other => other,
+    }
+}
+
+fn abbreviate(service: Value) -> Value {
+    rename(service, &ABBREVIATIONS)
+}
+
+fn expand(service: Value) -> Value {
+    let pairs: Vec<(&str, &str)> = ABBREVIATIONS
+        .iter()
+        .map(|&(long, short)| (short, long))
+        .collect();
+    rename(service, &pairs)
+}
+
+/// `use` of a decoded key: X25519 keys only agree on keys.
+fn key_use(jwk: &JWK) -> &'static str {
+    match jwk.params {
+        Params::OKP(ref params) if params.curve == "X25519" => "enc",
+        _ => "sig",
+    }
+}
+
+pub struct DIDPeer;
+
+impl DIDPeer {
+    /// numalgo 0: the DID wraps a single key.
+    pub fn numalgo0(jwk: &JWK) -> Option<String> {
+        Some(format!("did:peer:0{}", encode_key(jwk)?))
+    }
+
+    /// numalgo 2: each key is listed with its purpose, followed by the
+    /// services. The verification methods are `#key-1`, `#key-2`, ... in
+    /// that order.
+    pub fn numalgo2(keys: &[(Purpose, &JWK)], services: &[Value]) -> Option<String> {
+        let mut did = "did:peer:2".to_string();
+        for (purpose, jwk) in keys {
+            did.push('.');
+            did.push(purpose.prefix());
+            did.push_str(&encode_key(jwk)?);
+        }
+        for service in services {
+            let json = serde_json::to_vec(&abbreviate(service.clone())).ok()?;
+            did.push_str(".S");
+            did.push_str(&URL_SAFE_NO_PAD.encode(json));
+        }
+        Some(did)
+    }
+
+    /// The verification method of a numalgo 0 DID.
+    pub fn verification_method(did: &str) -> Option<String> {
+        let key = did.strip_prefix("did:peer:0")?;
+        Some(format!("{}#{}", did, key))
+    }
+
+    fn document(did: &str) -> Option<Document> {
+        let mut methods = Vec::new();
+        let mut relations = Map::new();
+        let mut services = Vec::new();
+        if let Some(key) = did.strip_prefix("did:peer:0") {
+            let jwk = decode_key(key)?;
+            let vm = format!("{}#{}", did, key);
+            relations = relationships(Some(key_use(&jwk)), &vm);
+            methods.push((vm, jwk));
+        } else if let Some(elements) = did.strip_prefix("did:peer:2.") {
+            for element in elements.split('.') {
+                let mut chars = element.chars();
+                let prefix = chars.next()?;
+                let rest = chars.as_str();
+                if prefix == 'S' {
+                    let json = URL_SAFE_NO_PAD.decode(rest).ok()?;
+                    let mut service = expand(serde_json::from_slice(&json).ok()?);
+                    let id = match services.len() {
+                        0 => format!("{}#service", did),
+                        n => format!("{}#service-{}", did, n),
+                    };
+                    service.as_object_mut()?.insert("id".to_string(), id.into());
+                    services.push(service);
+                    continue;
+                }
+                let purpose = Purpose::from_prefix(prefix)?;
+                let vm = format!("{}#key-{}", did, methods.len() + 1);
+                relations
+                    .entry(purpose.relationship())
+                    .or_insert_with(|| json!([]))
+                    .as_array_mut()?
+                    .push(vm.clone().into());
+                methods.push((vm, decode_key(rest)?));
+            }
+        } else {
+            return None;
+        }
+        let mut doc = json!({
+            "@context": ["https://www.w3.org/ns/did/v1", JWS_2020_CONTEXT],
+            "id": did,
+            "verificationMethod": methods
+                .into_iter()
+                .map(|(id, jwk)| json!({
+                    "id": id,
+                    "type": "JsonWebKey2020",
+                    "controller": did,
+                    "publicKeyJwk": jwk,
+                }))
+                .collect::<Vec<_>>(),
+        });
+        let object = doc.as_object_mut()?;
+        object.extend(relations);
+        if !services.is_empty() {
+            object.insert("service".to_string(), services.into());
+        }
+        serde_json::from_value(doc).ok()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl DIDResolver for DIDPeer {
+    async fn resolve(
+        &self,
+        did: &str,
+        _input_metadata: &ResolutionInputMetadata,
+    ) -> (
+        ResolutionMetadata,
+        Option<Document>,
+        Option<DocumentMetadata>,
+    ) {
+        match Self::document(did) {
+            Some(doc) => (
+                ResolutionMetadata {
+                    content_type: Some(TYPE_DID_LD_JSON.to_string()),
+                    ..Default::default()
+                },
+                Some(doc),
+                Some(DocumentMetadata::default()),
+            ),
+            None => (
+                ResolutionMetadata::from_error(ERROR_INVALID_DID),
+                None,
+                None,
+            ),
+        }
+    }
+
+    fn to_did_method(&self) -> Option<&dyn DIDMethod> {
+        Some(self)
+    }
+}
+
+impl DIDMethod for DIDPeer {
+    fn name(&self) -> &'static str {
+        "peer"
+    }
+
+    /// A numalgo 0 DID for the key.
+    fn generate(&self, source: &Source) -> Option<String> {
+        match source {
+            Source::Key(jwk) | Source::KeyAndPattern(jwk, "") => Self::numalgo0(jwk),
+            _ => None,
+        }
+    }
+
+    fn to_resolver(&self) -> &dyn DIDResolver {
+        self
+    }
+}
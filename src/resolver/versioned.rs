@@ -0,0 +1,322 @@
+//! Versioned DID documents: every update (such as a key rotation) is kept, so
+//! credentials can be checked against the document as it was when they were
+//! issued. Resolution honours `versionId` and `versionTime`, and reports
+//! `versionId`/`updated` (and `nextVersionId`/`nextUpdate` for superseded
+//! versions) in the document metadata. A history can end in a deactivation,
+//! reported as `deactivated: true`.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ssi_dids::did_resolve::{
+    DIDResolver, DocumentMetadata, Metadata, ResolutionInputMetadata, ResolutionMetadata,
+    ERROR_NOT_FOUND, TYPE_DID_LD_JSON,
+};
+use ssi_dids::Document;
+
+use crate::{Error, Result};
+
+pub const VERSION_ID: &str = "versionId";
+pub const NEXT_VERSION_ID: &str = "nextVersionId";
+pub const NEXT_UPDATE: &str = "nextUpdate";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Version {
+    pub version_id: String,
+    /// When this version replaced the previous one.
+    pub updated: DateTime<Utc>,
+    pub document: Value,
+    /// Whether this version deactivated the DID.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deactivated: bool,
+}
+
+/// All versions of one DID document, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DocumentHistory {
+    pub id: String,
+    pub versions: Vec<Version>,
+}
+
+impl DocumentHistory {
+    pub fn new(did: impl Into<String>) -> Self {
+        DocumentHistory {
+            id: did.into(),
+            versions: Vec::new(),
+        }
+    }
+
+    /// Appends `document` as the next version (`"1"`, `"2"`, ...), in effect
+    /// from `updated`.
+    pub fn push(&mut self, document: Value, updated: DateTime<Utc>) -> Result<&Version> {
+        self.append(document, updated, false)
+    }
+
+    /// Appends `document` as the last version, deactivating the DID from
+    /// `updated`.
+    pub fn deactivate(&mut self, document: Value, updated: DateTime<Utc>) -> Result<&Version> {
+        self.append(document, updated, true)
+    }
+
+    /// Whether the latest version deactivated the DID.
+    pub fn is_deactivated(&self) -> bool {
+        self.latest().is_some_and(|v| v.deactivated)
+    }
+
+    fn append(
+        &mut self,
+        document: Value,
+        updated: DateTime<Utc>,
+        deactivated: bool,
+    ) -> Result<&Version> {
+        if self.is_deactivated() {
+            return Err(Error::Encoding(format!("{} is deactivated", self.id)));
+        }
+        if document.get("id").and_then(Value::as_str) != Some(&self.id) {
+            return Err(Error::Encoding(format!("Document is not for {}", self.id)));
+        }
+        serde_json::from_value::<Document>(document.clone())?;
+        if let Some(latest) = self.latest() {
+            if updated <= latest.updated {
+                return Err(Error::Encoding(format!(
+                    "Version must be newer than {}",
+                    latest.updated
+                )));
+            }
+        }
+        self.versions.push(Version {
+            version_id: (self.versions.len() + 1).to_string(),
+            updated,
+            document,
+            deactivated,
+        });
+        Ok(&self.versions[self.versions.len() - 1])
+    }
+
+    pub fn latest(&self) -> Option<&Version> {
+        self.versions.last()
+    }
+
+    /// The version in effect at `time`.
+    pub fn at(&self, time: DateTime<Utc>) -> Option<&Version> {
+        self.versions.iter().rev().find(|v| v.updated <= time)
+    }
+
+    pub fn version(&self, version_id: &str) -> Option<&Version> {
+        self.versions.iter().find(|v| v.version_id == version_id)
+    }
+
+    fn metadata(&self, version: &Version) -> DocumentMetadata {
+        let mut properties = HashMap::from([(
+            VERSION_ID.to_string(),
+            Metadata::String(version.version_id.clone()),
+        )]);
+        let next = self
+            .versions
+            .iter()
+            .skip_while(|v| v.version_id != version.version_id)
+            .nth(1);
+        if let Some(next) = next {
+            properties.insert(
+                NEXT_VERSION_ID.to_string(),
+                Metadata::String(next.version_id.clone()),
+            );
+            properties.insert(
+                NEXT_UPDATE.to_string(),
+                Metadata::String(next.updated.to_rfc3339_opts(SecondsFormat::AutoSi, true)),
+            );
+        }
+        DocumentMetadata {
+            created: self.versions.first().map(|v| v.updated),
+            updated: Some(version.updated),
+            deactivated: version.deactivated.then_some(true),
+            property_set: Some(properties),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let json = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| Error::Encoding(format!("{}: {}", path.as_ref().display(), e)))?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path.as_ref(), json + "\n")
+            .map_err(|e| Error::Encoding(format!("{}: {}", path.as_ref().display(), e)))
+    }
+}
+
+/// Serves [`DocumentHistory`]s; new versions can be published while it is in
+/// use.
+#[derive(Default)]
+pub struct VersionedResolver {
+    histories: RwLock<HashMap<String, DocumentHistory>>,
+}
+
+impl VersionedResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads every `*.json` history in `dir`.
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        let resolver = Self::new();
+        let entries = std::fs::read_dir(dir.as_ref())
+            .map_err(|e| Error::Encoding(format!("{}: {}", dir.as_ref().display(), e)))?;
+        for entry in entries {
+            let path = entry.map_err(|e| Error::Encoding(e.to_string()))?.path();
+            if path.extension().is_some_and(|e| e == "json") {
+                resolver.insert(DocumentHistory::load(&path)?);
+            }
+        }
+        Ok(resolver)
+    }
+
+    pub fn insert(&self, history: DocumentHistory) {
+        self.histories
+            .write()
+            .unwrap()
+            .insert(history.id.clone(), history);
+    }
+
+    /// Publishes `document` as the newest version of its DID, returning the
+    /// new `versionId`.
+    pub fn publish(&self, document: Value, updated: DateTime<Utc>) -> Result<String> {
+        let did = document
+            .get("id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::Encoding("DID document without id".to_string()))?
+            .to_string();
+        let mut histories = self.histories.write().unwrap();
+        let history = histories
+            .entry(did.clone())
+            .or_insert_with(|| DocumentHistory::new(did));
+        Ok(history.push(document, updated)?.version_id.clone())
+    }
+
+    /// Deactivates `did` from `updated`, as a last version repeating the
+    /// latest document, returning its `versionId`.
+    pub fn deactivate(&self, did: &str, updated: DateTime<Utc>) -> Result<String> {
+        let mut histories = self.histories.write().unwrap();
+        let history = histories.get_mut(did).ok_or_else(|| Error::Resolution {
+            did: did.to_string(),
+            reason: ERROR_NOT_FOUND.to_string(),
+        })?;
+        let document = match history.latest() {
+            Some(latest) => latest.document.clone(),
+            None => {
+                return Err(Error::Resolution {
+                    did: did.to_string(),
+                    reason: "No versions".to_string(),
+                })
+            }
+        };
+        Ok(history.deactivate(document, updated)?.version_id.clone())
+    }
+
+    pub fn history(&self, did: &str) -> Option<DocumentHistory> {
+        self.histories.read().unwrap().get(did).cloned()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl DIDResolver for VersionedResolver {
+    async fn resolve(
+        &self,
+        did: &str,
+        input_metadata: &ResolutionInputMetadata,
+    ) -> (
+        ResolutionMetadata,
+        Option<Document>,
+        Option<DocumentMetadata>,
+    ) {
+        let histories = self.histories.read().unwrap();
+        let Some(history) = histories.get(did) else {
+            return (ResolutionMetadata::from_error(ERROR_NOT_FOUND), None, None);
+        };
+        let version = match (&input_metadata.version_id, &input_metadata.version_time) {
+            (Some(id), _) => history.version(id),
+            (None, Some(time)) => match DateTime::parse_from_rfc3339(time) {
+                Ok(time) => history.at(time.with_timezone(&Utc)),
+                Err(e) => {
+                    return (
+                        ResolutionMetadata::from_error(&format!(
+                            "Invalid versionTime {}: {}",
+                            time, e
+                        )),
+                        None,
+                        None,
+                    )
+                }
+            },
+            (None, None) => history.latest(),
+        };
+        let Some(version) = version else {
+            return (ResolutionMetadata::from_error(ERROR_NOT_FOUND), None, None);
+        };
+        match serde_json::from_value(version.document.clone()) {
+            Ok(doc) => (
+                ResolutionMetadata {
+                    content_type: Some(TYPE_DID_LD_JSON.to_string()),
+                    ..Default::default()
+                },
+                Some(doc),
+                Some(history.metadata(version)),
+            ),
+            Err(e) => (
+                ResolutionMetadata::from_error(&format!("Invalid DID document: {}", e)),
+                None,
+                None,
+            ),
+        }
+    }
+}
+
+/// Resolves as of `time`: adds `versionTime` to requests that name no
+/// version, so every lookup made while verifying a credential sees the
+/// documents in effect when it was issued. Resolvers without versions ignore
+/// it.
+pub struct AsOf<'a> {
+    inner: &'a dyn DIDResolver,
+    time: String,
+}
+
+impl<'a> AsOf<'a> {
+    pub fn new(inner: &'a dyn DIDResolver, time: DateTime<Utc>) -> Self {
+        AsOf {
+            inner,
+            time: time.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl DIDResolver for AsOf<'_> {
+    async fn resolve(
+        &self,
+        did: &str,
+        input_metadata: &ResolutionInputMetadata,
+    ) -> (
+        ResolutionMetadata,
+        Option<Document>,
+        Option<DocumentMetadata>,
+    ) {
+        if input_metadata.version_id.is_some() || input_metadata.version_time.is_some() {
+            return self.inner.resolve(did, input_metadata).await;
+        }
+        let input_metadata = ResolutionInputMetadata {
+            version_time: Some(self.time.clone()),
+            ..input_metadata.clone()
+        };
+        self.inner.resolve(did, &input_metadata).await
+    }
+}
@@ -0,0 +1,188 @@
+//! did:web resolution: `did:web:<domain>[:<path>...]` is fetched from
+//! `https://<domain>/.well-known/did.json`, or `https://<domain>/<path>/did.json`
+//! for path-based DIDs. The port, if any, is percent-encoded in the domain
+//! (`did:web:localhost%3A8443`).
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use ssi_dids::did_resolve::{
+    DIDResolver, DocumentMetadata, ResolutionInputMetadata, ResolutionMetadata, ERROR_INVALID_DID,
+    ERROR_NOT_FOUND, TYPE_DID_LD_JSON,
+};
+use ssi_dids::Document;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// Largest DID document [`ReqwestClient`] reads.
+pub const MAX_BODY: usize = 1024 * 1024;
+
+/// Fetches a URL; injected into [`DIDWeb`] so tests and constrained builds can
+/// swap the transport.
+#[async_trait]
+pub trait HttpClient: Send + Sync {
+    /// Status code and body of `GET url`.
+    async fn get(&self, url: &str) -> Result<(u16, Vec<u8>), String>;
+}
+
+/// The default client: reqwest with rustls. Redirects are not followed, so
+/// the document comes from the host the DID names, and bodies over
+/// [`MAX_BODY`] are refused.
+pub struct ReqwestClient {
+    client: reqwest::Client,
+}
+
+impl Default for ReqwestClient {
+    fn default() -> Self {
+        ReqwestClient {
+            client: reqwest::Client::builder()
+                .timeout(TIMEOUT)
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[async_trait]
+impl HttpClient for ReqwestClient {
+    async fn get(&self, url: &str) -> Result<(u16, Vec<u8>), String> {
+        let mut response = self
+            .client
+            .get(url)
+            .header("accept", "application/did+json, application/json")
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = response.status().as_u16();
+        let too_large = || format!("Response larger than {} bytes", MAX_BODY);
+        if response
+            .content_length()
+            .is_some_and(|length| length > MAX_BODY as u64)
+        {
+            return Err(too_large());
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            if body.len() + chunk.len() > MAX_BODY {
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok((status, body))
+    }
+}
+
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3)?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+/// The `did.json` URL for a did:web DID, or `None` if it is not a valid one.
+pub fn did_web_url(did: &str) -> Option<String> {
+    let id = did.strip_prefix("did:web:")?;
+    let mut segments = id.split(':');
+    let domain = percent_decode(segments.next()?)?;
+    if domain.is_empty() || domain.contains(['/', '?', '#', '@']) {
+        return None;
+    }
+    let path = segments
+        .map(|segment| match percent_decode(segment) {
+            Some(s) if !s.is_empty() && !s.contains('/') && s != "." && s != ".." => Some(s),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(if path.is_empty() {
+        format!("https://{}/.well-known/did.json", domain)
+    } else {
+        format!("https://{}/{}/did.json", domain, path.join("/"))
+    })
+}
+
+/// did:web resolver. The fetched document must carry the DID being resolved
+/// as its `id`, so a host cannot serve another DID's keys.
+pub struct DIDWeb {
+    client: Box<dyn HttpClient>,
+}
+
+impl Default for DIDWeb {
+    fn default() -> Self {
+        Self::with_client(ReqwestClient::default())
+    }
+}
+
+impl DIDWeb {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_client(client: impl HttpClient + 'static) -> Self {
+        DIDWeb {
+            client: Box::new(client),
+        }
+    }
+}
+
+fn error(
+    message: impl Into<String>,
+) -> (
+    ResolutionMetadata,
+    Option<Document>,
+    Option<DocumentMetadata>,
+) {
+    (ResolutionMetadata::from_error(&message.into()), None, None)
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl DIDResolver for DIDWeb {
+    async fn resolve(
+        &self,
+        did: &str,
+        _input_metadata: &ResolutionInputMetadata,
+    ) -> (
+        ResolutionMetadata,
+        Option<Document>,
+        Option<DocumentMetadata>,
+    ) {
+        let url = match did_web_url(did) {
+            Some(url) => url,
+            None => return error(ERROR_INVALID_DID),
+        };
+        let body = match self.client.get(&url).await {
+            Ok((200, body)) => body,
+            Ok((404, _)) => return error(ERROR_NOT_FOUND),
+            Ok((status, _)) => return error(format!("HTTP {} from {}", status, url)),
+            Err(e) => return error(format!("Unable to fetch {}: {}", url, e)),
+        };
+        let doc: Document = match serde_json::from_slice(&body) {
+            Ok(doc) => doc,
+            Err(e) => return error(format!("Invalid DID document at {}: {}", url, e)),
+        };
+        if doc.id != did {
+            return error(format!(
+                "Document at {} is for {}, not {}",
+                url, doc.id, did
+            ));
+        }
+        (
+            ResolutionMetadata {
+                content_type: Some(TYPE_DID_LD_JSON.to_string()),
+                ..Default::default()
+            },
+            Some(doc),
+            Some(DocumentMetadata::default()),
+        )
+    }
+}
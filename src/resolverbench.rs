@@ -0,0 +1,83 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use didkemb::cli::Args;
+use didkemb::measure::LatencySummary;
+use didkemb::resolver::CachingResolver;
+use didkemb::verifier::Verifier;
+use didkemb::DIDExampleStatic;
+use ssi_dids::did_resolve::{DIDResolver, ResolutionInputMetadata};
+
+fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+// resolver-bench [--iterations 200] [--claims 10] [--ttl-secs 60] [--capacity 64]
+// Verifica la stessa credenziale con e senza cache del resolver, e misura a
+// parte il costo della sola risoluzione del documento DID.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    didkemb::runtime::block_on(run())?
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::from_env();
+    let iterations: u32 = args.parse_or("iterations", 200)?;
+    let claims: usize = args.parse_or("claims", 10)?;
+    let ttl = Duration::from_secs(args.parse_or("ttl-secs", 60)?);
+    let capacity: usize = args.parse_or("capacity", 64)?;
+
+    let key = didkemb::issuer_key()?;
+    let vc = didkemb::example_credential(claims)?;
+    let jwt = didkemb::issue_jwt(&vc, &key, &didkemb::proof_options(), &DIDExampleStatic).await?;
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        DIDExampleStatic
+            .resolve(didkemb::ISSUER_DID, &ResolutionInputMetadata::default())
+            .await;
+    }
+    eprintln!(
+        "resolve only: {:.1} us per DID document",
+        start.elapsed().as_secs_f64() * 1e6 / iterations as f64
+    );
+
+    let cached = Arc::new(CachingResolver::new(DIDExampleStatic, ttl, capacity));
+    println!("resolver,iterations,verify_mean_ms,verify_p50_ms,verify_p95_ms,hits,misses,mean_resolve_us");
+    for (name, resolver) in [
+        (
+            "static",
+            Arc::new(DIDExampleStatic) as Arc<dyn DIDResolver + Send>,
+        ),
+        ("cached", cached.clone() as Arc<dyn DIDResolver + Send>),
+    ] {
+        let verifier = Verifier::new(resolver);
+        let mut latencies = Vec::new();
+        for _ in 0..iterations {
+            let t = Instant::now();
+            verifier.verify(&jwt).await.into_result()?;
+            latencies.push(t.elapsed());
+        }
+        let Some(s) = LatencySummary::from_samples(latencies) else {
+            return Err(
+                didkemb::Error::Usage("--iterations must be at least 1".to_string()).into(),
+            );
+        };
+        let stats = if name == "cached" {
+            cached.stats()
+        } else {
+            Default::default()
+        };
+        println!(
+            "{},{},{:.3},{:.3},{:.3},{},{},{:.1}",
+            name,
+            iterations,
+            ms(s.mean),
+            ms(s.p50),
+            ms(s.p95),
+            stats.hits,
+            stats.misses,
+            stats.mean_resolve_time().as_secs_f64() * 1e6,
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,126 @@
+//! Tokio runtime selection for the binaries. The benchmarks are mostly
+//! sequential, so a current-thread runtime saves the worker threads and their
+//! stacks; the multi-threaded one stays the default.
+//!
+//! The flavor comes from `--runtime current_thread|multi_thread` (with
+//! `--workers N`) or, for binaries without flags, from `DIDKEMB_RUNTIME` and
+//! `DIDKEMB_WORKERS`.
+
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+
+use tokio::runtime::{Builder, Runtime};
+
+use crate::cli::Args;
+use crate::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flavor {
+    CurrentThread,
+    /// `None` uses one worker per core.
+    MultiThread {
+        workers: Option<usize>,
+    },
+}
+
+impl Default for Flavor {
+    fn default() -> Self {
+        if cfg!(feature = "multi-thread") {
+            Flavor::MultiThread { workers: None }
+        } else {
+            Flavor::CurrentThread
+        }
+    }
+}
+
+impl fmt::Display for Flavor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Flavor::CurrentThread => write!(f, "current_thread"),
+            Flavor::MultiThread { workers: None } => write!(f, "multi_thread"),
+            Flavor::MultiThread { workers: Some(n) } => write!(f, "multi_thread:{}", n),
+        }
+    }
+}
+
+/// Parses the [`Display`](fmt::Display) form: `current_thread`,
+/// `multi_thread` or `multi_thread:N`.
+impl FromStr for Flavor {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, workers) = match s.split_once(':') {
+            Some((name, n)) => (
+                name,
+                Some(
+                    n.parse()
+                        .map_err(|e| Error::Usage(format!("workers {}: {}", n, e)))?,
+                ),
+            ),
+            None => (s, None),
+        };
+        match (name, workers) {
+            ("current_thread", None) => Ok(Flavor::CurrentThread),
+            ("multi_thread", Some(0)) => {
+                Err(Error::Usage("workers must be at least 1".to_string()))
+            }
+            ("multi_thread", workers) => Ok(Flavor::MultiThread { workers }),
+            _ => Err(Error::Usage(format!("Unknown runtime {}", s))),
+        }
+    }
+}
+
+impl Flavor {
+    /// `--runtime`/`--workers` if given, else `DIDKEMB_RUNTIME`/`DIDKEMB_WORKERS`,
+    /// else the default.
+    pub fn from_args(args: &Args) -> Result<Self> {
+        let env = |name: &str| std::env::var(name).ok();
+        let name = args
+            .value("runtime")
+            .map(str::to_string)
+            .or_else(|| env("DIDKEMB_RUNTIME"));
+        let workers = args
+            .value("workers")
+            .map(str::to_string)
+            .or_else(|| env("DIDKEMB_WORKERS"));
+        match (name, workers) {
+            (None, None) => Ok(Flavor::default()),
+            (name, Some(n)) => {
+                format!("{}:{}", name.as_deref().unwrap_or("multi_thread"), n).parse()
+            }
+            (Some(name), None) => name.parse(),
+        }
+    }
+
+    pub fn build(&self) -> Result<Runtime> {
+        let mut builder = match self {
+            Flavor::CurrentThread => Builder::new_current_thread(),
+            #[cfg(feature = "multi-thread")]
+            Flavor::MultiThread { workers } => {
+                let mut builder = Builder::new_multi_thread();
+                if let Some(n) = workers {
+                    builder.worker_threads(*n);
+                }
+                builder
+            }
+            #[cfg(not(feature = "multi-thread"))]
+            Flavor::MultiThread { .. } => {
+                return Err(Error::Usage(
+                    "Built without the multi-thread feature".to_string(),
+                ))
+            }
+        };
+        builder
+            .enable_all()
+            .build()
+            .map_err(|e| Error::Usage(format!("Unable to start {} runtime: {}", self, e)))
+    }
+}
+
+/// Runs `future` to completion on the runtime selected by the command line or
+/// environment; what `#[tokio::main]` does with a fixed flavor.
+pub fn block_on<F: Future>(future: F) -> Result<F::Output> {
+    let runtime = Flavor::from_args(&Args::from_env())?.build()?;
+    Ok(runtime.block_on(future))
+}
@@ -0,0 +1,90 @@
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use didkemb::cli::Args;
+use didkemb::measure::{LatencySummary, ProcessMonitor};
+use didkemb::runtime::Flavor;
+use didkemb::DIDExampleStatic;
+
+fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+// runtime-bench [--runtimes current_thread,multi_thread:1,multi_thread:2,multi_thread]
+//               [--iterations 50] [--claims 10]
+// Ogni runtime gira in un processo figlio, così RSS e thread non si sommano.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::from_env();
+    if args.flag("child") {
+        return child(&args);
+    }
+    let iterations: u32 = args.parse_or("iterations", 50)?;
+    let claims: usize = args.parse_or("claims", 10)?;
+    let runtimes = args
+        .value("runtimes")
+        .unwrap_or("current_thread,multi_thread:1,multi_thread:2,multi_thread")
+        .split(',')
+        .map(str::parse)
+        .collect::<Result<Vec<Flavor>, _>>()?;
+
+    println!("runtime,iterations,mean_ms,p50_ms,p95_ms,max_ms,rss_kb,threads,cpu_user_ms");
+    for flavor in runtimes {
+        let output = Command::new(std::env::current_exe()?)
+            .env_remove("DIDKEMB_RUNTIME")
+            .env_remove("DIDKEMB_WORKERS")
+            .args(["--child", "--runtime", &flavor.to_string()])
+            .args(["--iterations", &iterations.to_string()])
+            .args(["--claims", &claims.to_string()])
+            .output()?;
+        if !output.status.success() {
+            eprintln!("{}: {}", flavor, String::from_utf8_lossy(&output.stderr));
+            continue;
+        }
+        print!("{}", String::from_utf8_lossy(&output.stdout));
+    }
+    Ok(())
+}
+
+fn child(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let flavor = Flavor::from_args(args)?;
+    let iterations: u32 = args.parse_or("iterations", 50)?;
+    let claims: usize = args.parse_or("claims", 10)?;
+    let process = ProcessMonitor::current()?;
+    let start = process.sample()?;
+
+    let runtime = flavor.build()?;
+    let latencies = runtime.block_on(async {
+        let key = didkemb::issuer_key()?;
+        let options = didkemb::proof_options();
+        let mut context_loader = didkemb::context::loader();
+        let mut latencies = Vec::new();
+        for _ in 0..iterations {
+            let vc = didkemb::example_credential(claims)?;
+            let t = Instant::now();
+            let jwt = didkemb::issue_jwt(&vc, &key, &options, &DIDExampleStatic).await?;
+            didkemb::report::verify_jwt(&jwt, None, &DIDExampleStatic, &mut context_loader)
+                .await
+                .into_result()?;
+            latencies.push(t.elapsed());
+        }
+        Ok::<_, didkemb::Error>(latencies)
+    })?;
+    let usage = process.sample()?;
+
+    let Some(s) = LatencySummary::from_samples(latencies) else {
+        return Err(didkemb::Error::Usage("--iterations must be at least 1".to_string()).into());
+    };
+    println!(
+        "{},{},{:.3},{:.3},{:.3},{:.3},{},{},{:.1}",
+        flavor,
+        iterations,
+        ms(s.mean),
+        ms(s.p50),
+        ms(s.p95),
+        ms(s.max),
+        usage.rss / 1024,
+        usage.threads,
+        ms(usage.since(&start).cpu_user),
+    );
+    Ok(())
+}
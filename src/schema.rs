@@ -0,0 +1,179 @@
+//! `credentialSchema` validation (`JsonSchema2023`): each credential subject
+//! is checked against the JSON Schemas the credential names, taken from a
+//! local registry and never fetched. Errors point at the offending value.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use jsonschema::JSONSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ssi::one_or_many::OneOrMany;
+use ssi::vc::{Credential, Schema, URI};
+
+use crate::{Error, Result};
+
+pub const JSON_SCHEMA_2023: &str = "JsonSchema2023";
+/// The type's name in the final VC JSON Schema specification.
+pub const JSON_SCHEMA: &str = "JsonSchema";
+
+/// One validation failure.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaError {
+    /// JSON pointer into the credential, such as `/credentialSubject/claim1`.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.pointer.as_str() {
+            "" => write!(f, "{}", self.message),
+            pointer => write!(f, "{}: {}", pointer, self.message),
+        }
+    }
+}
+
+/// JSON Schemas by `$id`, each compiled once, on first use.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaRegistry {
+    schemas: BTreeMap<String, Value>,
+    compiled: Arc<RwLock<BTreeMap<String, Arc<JSONSchema>>>>,
+}
+
+impl SchemaRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads every `*.json` schema in `dir`, keyed by its `$id`.
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        let mut registry = Self::new();
+        let entries = std::fs::read_dir(dir.as_ref())
+            .map_err(|e| Error::Encoding(format!("{}: {}", dir.as_ref().display(), e)))?;
+        for entry in entries {
+            let path = entry.map_err(|e| Error::Encoding(e.to_string()))?.path();
+            if path.extension().is_some_and(|e| e == "json") {
+                let json = std::fs::read_to_string(&path)
+                    .map_err(|e| Error::Encoding(format!("{}: {}", path.display(), e)))?;
+                registry.insert(serde_json::from_str(&json)?)?;
+            }
+        }
+        Ok(registry)
+    }
+
+    /// Adds `schema` under its `$id`, returning the id.
+    pub fn insert(&mut self, schema: Value) -> Result<String> {
+        let id = schema
+            .get("$id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::Encoding("Schema without $id".to_string()))?
+            .to_string();
+        JSONSchema::compile(&schema).map_err(|e| Error::Encoding(format!("{}: {}", id, e)))?;
+        self.schemas.insert(id.clone(), schema);
+        // A new schema can change what `$ref`s resolve to; clones keep theirs.
+        self.compiled = Default::default();
+        Ok(id)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Value> {
+        self.schemas.get(id)
+    }
+
+    /// `id` compiled, with the other schemas available to `$ref`.
+    fn compile(&self, id: &str) -> Result<Arc<JSONSchema>, String> {
+        if let Some(compiled) = self.compiled.read().unwrap().get(id) {
+            return Ok(compiled.clone());
+        }
+        let schema = self
+            .get(id)
+            .ok_or_else(|| format!("Unknown schema {}", id))?;
+        let mut options = JSONSchema::options();
+        for (other, document) in &self.schemas {
+            options.with_document(other.clone(), document.clone());
+        }
+        let compiled = Arc::new(
+            options
+                .compile(schema)
+                .map_err(|e| format!("{}: {}", id, e))?,
+        );
+        self.compiled
+            .write()
+            .unwrap()
+            .insert(id.to_string(), compiled.clone());
+        Ok(compiled)
+    }
+
+    /// Validates every credential subject against every `JsonSchema2023`
+    /// entry of `credentialSchema`; other schema types are left alone.
+    pub fn validate(&self, vc: &Credential) -> Result<(), Vec<SchemaError>> {
+        let error = |pointer: &str, message: String| SchemaError {
+            pointer: pointer.to_string(),
+            message,
+        };
+        let subjects: Vec<Value> = match serde_json::to_value(&vc.credential_subject) {
+            Ok(Value::Array(subjects)) => subjects,
+            Ok(subject) => vec![subject],
+            Err(e) => return Err(vec![error("/credentialSubject", e.to_string())]),
+        };
+        let many = subjects.len() > 1;
+        let mut errors = Vec::new();
+        for schema in vc.credential_schema.iter().flatten() {
+            if schema.type_ != JSON_SCHEMA_2023 && schema.type_ != JSON_SCHEMA {
+                continue;
+            }
+            let compiled = match self.compile(&schema.id.to_string()) {
+                Ok(compiled) => compiled,
+                Err(e) => {
+                    errors.push(error("/credentialSchema", e));
+                    continue;
+                }
+            };
+            for (i, subject) in subjects.iter().enumerate() {
+                let prefix = match many {
+                    true => format!("/credentialSubject/{}", i),
+                    false => "/credentialSubject".to_string(),
+                };
+                if let Err(failures) = compiled.validate(subject) {
+                    errors.extend(
+                        failures.map(|e| {
+                            error(&format!("{}{}", prefix, e.instance_path), e.to_string())
+                        }),
+                    );
+                }
+            }
+        }
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+}
+
+/// Names `id` as the credential's `JsonSchema2023` schema.
+pub fn attach(vc: &mut Credential, id: &str) {
+    let schema = Schema {
+        id: URI::String(id.to_string()),
+        type_: JSON_SCHEMA_2023.to_string(),
+        property_set: None,
+    };
+    vc.credential_schema = Some(match vc.credential_schema.take() {
+        None => OneOrMany::One(schema),
+        Some(OneOrMany::One(first)) => OneOrMany::Many(vec![first, schema]),
+        Some(OneOrMany::Many(mut all)) => {
+            all.push(schema);
+            OneOrMany::Many(all)
+        }
+    });
+}
+
+/// `"/a: ...; /b: ..."`, for error messages.
+pub fn describe(errors: &[SchemaError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
@@ -0,0 +1,371 @@
+//! Credential templates kept in TOML or JSON files: contexts, types, issuer,
+//! subject fields with `{{variable}}` placeholders, an expiration rule and a
+//! `claimN` generator. New credential kinds need a file, not a rebuild.
+//!
+//! ```toml
+//! types = ["VerifiableCredential", "FirmwareAttestation"]
+//! issuer = "{{issuer}}"
+//! expiration = { after = "30d" }
+//!
+//! [subject]
+//! id = "{{subject}}"
+//! firmware = "{{version}}"
+//!
+//! [variables]
+//! issuer = "did:key:z6Mk..."
+//! ```
+//!
+//! Besides the template's own defaults, `{{uuid}}` (a fresh v4 UUID) and
+//! `{{now}}` (the issuance date) are always available. A generator makes at
+//! most [`MAX_CLAIMS`] claims.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Read;
+use std::path::Path;
+
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use ssi::vc::Credential;
+
+use crate::cli::Args;
+use crate::datamodel::DataModel;
+use crate::{Error, Result};
+
+pub type Variables = BTreeMap<String, String>;
+
+/// Variables every rendering sets.
+pub const BUILT_IN: [&str; 2] = ["uuid", "now"];
+/// Most claims a [`ClaimGenerator`] makes.
+pub const MAX_CLAIMS: usize = 10_000;
+
+/// The benchmark credential: a hash and `claims` generated claims.
+pub const BENCHMARK: &str = include_str!("../templates/benchmark.toml");
+
+/// When the credential expires: `after` its issuance (`90s`, `15m`, `12h`,
+/// `30d`) or `at` a fixed RFC 3339 date.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Expiration {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at: Option<String>,
+}
+
+/// Generates `count` subject claims named `name` with value `value`, where
+/// `{n}` is the claim number from 1.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClaimGenerator {
+    /// A number, or a placeholder such as `"{{claims}}"`.
+    pub count: Value,
+    #[serde(default = "ClaimGenerator::default_name")]
+    pub name: String,
+    #[serde(default = "ClaimGenerator::default_value")]
+    pub value: String,
+}
+
+impl ClaimGenerator {
+    fn default_name() -> String {
+        "claim{n}".to_string()
+    }
+
+    fn default_value() -> String {
+        "value {n}".to_string()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CredentialTemplate {
+    /// Defaults to the VC 1.1 context alone.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contexts: Vec<Value>,
+    /// Defaults to `VerifiableCredential` alone.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub types: Vec<String>,
+    pub issuer: String,
+    #[serde(default)]
+    pub subject: Map<String, Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<Expiration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claims: Option<ClaimGenerator>,
+    /// `$id` of a schema to name in `credentialSchema`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    /// Default values of the variables.
+    #[serde(default)]
+    pub variables: Variables,
+}
+
+impl CredentialTemplate {
+    /// Parses TOML, or JSON when `source` is a JSON object.
+    pub fn parse(source: &str) -> Result<Self> {
+        if source.trim_start().starts_with('{') {
+            serde_json::from_str(source).map_err(|e| Error::Template(e.to_string()))
+        } else {
+            toml::from_str(source).map_err(|e| Error::Template(e.to_string()))
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let source = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| Error::Template(format!("{}: {}", path.as_ref().display(), e)))?;
+        Self::parse(&source)
+    }
+
+    /// Every `*.toml` and `*.json` template in `dir`, named by file stem.
+    pub fn load_dir(dir: impl AsRef<Path>) -> Result<BTreeMap<String, Self>> {
+        let entries = std::fs::read_dir(dir.as_ref())
+            .map_err(|e| Error::Template(format!("{}: {}", dir.as_ref().display(), e)))?;
+        let mut templates = BTreeMap::new();
+        for entry in entries {
+            let path = entry.map_err(|e| Error::Template(e.to_string()))?.path();
+            let name = path.file_stem().and_then(|s| s.to_str());
+            let extension = path.extension().and_then(|e| e.to_str());
+            if let (Some(name), Some("toml" | "json")) = (name, extension) {
+                templates.insert(name.to_string(), Self::load(&path)?);
+            }
+        }
+        Ok(templates)
+    }
+
+    /// The built-in [`BENCHMARK`] template.
+    pub fn benchmark() -> Self {
+        Self::parse(BENCHMARK).expect("benchmark template is valid")
+    }
+
+    /// Placeholders a caller has to fill: used by the template, without a
+    /// default and not [`BUILT_IN`].
+    pub fn inputs(&self) -> BTreeSet<String> {
+        let mut template = serde_json::to_value(self).unwrap_or_default();
+        if let Some(template) = template.as_object_mut() {
+            template.remove("variables");
+        }
+        let mut names = BTreeSet::new();
+        placeholders(&template, &mut names);
+        names.retain(|name| {
+            !self.variables.contains_key(name) && !BUILT_IN.contains(&name.as_str())
+        });
+        names
+    }
+
+    /// The credential, with `variables` overriding the template's defaults.
+    pub fn render(&self, variables: &Variables) -> Result<Credential> {
+        serde_json::from_value(self.render_as(variables, DataModel::V1)?)
+            .map_err(|e| Error::Template(e.to_string()))
+    }
+
+    /// The credential as JSON, written for `model`.
+    pub fn render_as(&self, variables: &Variables, model: DataModel) -> Result<Value> {
+        let issuance = ssi::ldp::now_ns();
+        let mut vars = self.variables.clone();
+        vars.insert("uuid".to_string(), uuid::Uuid::new_v4().to_string());
+        vars.insert(
+            "now".to_string(),
+            issuance.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        );
+        vars.extend(variables.clone());
+
+        let contexts = match self.contexts.is_empty() {
+            true => vec![Value::from("https://www.w3.org/2018/credentials/v1")],
+            false => self.contexts.clone(),
+        };
+        let types: Value = match self.types.as_slice() {
+            [] => "VerifiableCredential".into(),
+            [only] => only.as_str().into(),
+            types => types.into(),
+        };
+        let mut subject = substitute(&Value::Object(self.subject.clone()), &vars)?;
+        if let Some(ref generator) = self.claims {
+            let subject = subject
+                .as_object_mut()
+                .ok_or_else(|| Error::Template("subject is not a table".to_string()))?;
+            for n in 1..=generator.count(&vars)? {
+                let n = n.to_string();
+                subject.insert(
+                    generator.name.replace("{n}", &n),
+                    generator.value.replace("{n}", &n).into(),
+                );
+            }
+        }
+        let mut vc = serde_json::json!({
+            "@context": substitute(&Value::Array(contexts), &vars)?,
+            "type": types,
+            "issuer": fill(&self.issuer, &vars)?,
+            "issuanceDate": issuance,
+            "credentialSubject": subject,
+        });
+        if let Some(ref expiration) = self.expiration {
+            vc["expirationDate"] = expiration.date(issuance, &vars)?.into();
+        }
+        if let Some(ref schema) = self.schema {
+            vc["credentialSchema"] = serde_json::json!({
+                "id": fill(schema, &vars)?,
+                "type": crate::schema::JSON_SCHEMA_2023,
+            });
+        }
+        Ok(model.convert(vc))
+    }
+}
+
+impl ClaimGenerator {
+    fn count(&self, vars: &Variables) -> Result<usize> {
+        let count = match self.count {
+            Value::Number(ref n) => n.to_string(),
+            Value::String(ref s) => fill(s, vars)?,
+            _ => return Err(Error::Template("claims.count is not a number".to_string())),
+        };
+        let n: usize = count
+            .parse()
+            .map_err(|_| Error::Template(format!("claims.count {} is not a number", count)))?;
+        match n <= MAX_CLAIMS {
+            true => Ok(n),
+            false => Err(Error::Template(format!(
+                "claims.count {} is over {}",
+                n, MAX_CLAIMS
+            ))),
+        }
+    }
+}
+
+impl Expiration {
+    fn date(&self, issuance: DateTime<Utc>, vars: &Variables) -> Result<String> {
+        match (&self.after, &self.at) {
+            (Some(after), None) => {
+                let after = fill(after, vars)?;
+                let expiration = issuance
+                    .checked_add_signed(duration(&after)?)
+                    .ok_or_else(|| Error::Template(format!("Invalid duration {}", after)))?;
+                Ok(expiration.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            }
+            (None, Some(at)) => {
+                let at = fill(at, vars)?;
+                DateTime::parse_from_rfc3339(&at)
+                    .map_err(|e| Error::Template(format!("expiration.at {}: {}", at, e)))?;
+                Ok(at)
+            }
+            _ => Err(Error::Template(
+                "expiration needs either after or at".to_string(),
+            )),
+        }
+    }
+}
+
+/// `90s`, `15m`, `12h` or `30d`.
+fn duration(s: &str) -> Result<Duration> {
+    let invalid = || Error::Template(format!("Invalid duration {}", s));
+    let (at, unit) = s.char_indices().last().ok_or_else(invalid)?;
+    let amount: i64 = s[..at].parse().map_err(|_| invalid())?;
+    match unit {
+        's' => Duration::try_seconds(amount),
+        'm' => Duration::try_minutes(amount),
+        'h' => Duration::try_hours(amount),
+        'd' => Duration::try_days(amount),
+        _ => None,
+    }
+    .ok_or_else(invalid)
+}
+
+/// Replaces every `{{name}}` in `s`.
+fn fill(s: &str, vars: &Variables) -> Result<String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find("{{") {
+        let end = rest[start..]
+            .find("}}")
+            .ok_or_else(|| Error::Template(format!("Unclosed placeholder in {}", s)))?;
+        let name = rest[start + 2..start + end].trim();
+        let value = vars
+            .get(name)
+            .ok_or_else(|| Error::Template(format!("Missing variable {}", name)))?;
+        out.push_str(&rest[..start]);
+        out.push_str(value);
+        rest = &rest[start + end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// [`fill`] on every string in `value`.
+fn substitute(value: &Value, vars: &Variables) -> Result<Value> {
+    Ok(match value {
+        Value::String(s) => fill(s, vars)?.into(),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|v| substitute(v, vars))
+                .collect::<Result<_>>()?,
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| Ok((k.clone(), substitute(v, vars)?)))
+                .collect::<Result<_>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+/// Adds the name of every `{{name}}` in `value` to `names`.
+fn placeholders(value: &Value, names: &mut BTreeSet<String>) {
+    match value {
+        Value::String(s) => {
+            let mut rest = s.as_str();
+            while let Some(start) = rest.find("{{") {
+                let Some(end) = rest[start..].find("}}") else {
+                    break;
+                };
+                names.insert(rest[start + 2..start + end].trim().to_string());
+                rest = &rest[start + end + 2..];
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|v| placeholders(v, names)),
+        Value::Object(map) => map.values().for_each(|v| placeholders(v, names)),
+        _ => {}
+    }
+}
+
+/// Variables from a JSON object; non-string values are used as written.
+pub fn parse_variables(json: &str) -> Result<Variables> {
+    let object: Map<String, Value> = serde_json::from_str(json)?;
+    Ok(object
+        .into_iter()
+        .map(|(k, v)| match v {
+            Value::String(s) => (k, s),
+            v => (k, v.to_string()),
+        })
+        .collect())
+}
+
+/// The template and variables picked on the command line: `--template FILE`
+/// (the benchmark template otherwise), then `defaults`, a JSON object from
+/// `--vars FILE` (`-` for stdin) and each `--var name=value`, later ones
+/// winning.
+pub fn from_args(
+    args: &Args,
+    defaults: &[(&str, &str)],
+) -> Result<(CredentialTemplate, Variables)> {
+    let template = match args.value("template") {
+        Some(path) => CredentialTemplate::load(path)?,
+        None => CredentialTemplate::benchmark(),
+    };
+    let mut vars: Variables = defaults
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    if let Some(source) = args.value("vars") {
+        let mut json = String::new();
+        match source {
+            "-" => std::io::stdin().read_to_string(&mut json),
+            path => std::fs::File::open(path).and_then(|mut f| f.read_to_string(&mut json)),
+        }
+        .map_err(|e| Error::Usage(format!("--vars {}: {}", source, e)))?;
+        vars.extend(parse_variables(&json)?);
+    }
+    for var in args.values("var") {
+        let (name, value) = var
+            .split_once('=')
+            .ok_or_else(|| Error::Usage(format!("--var {}: expected name=value", var)))?;
+        vars.insert(name.to_string(), value.to_string());
+    }
+    Ok((template, vars))
+}
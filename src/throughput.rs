@@ -0,0 +1,111 @@
+//! Throughput under load: K tasks issue and verify credentials back to back
+//! for a fixed duration, sharing one issuer and one verifier.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use ssi_dids::did_resolve::DIDResolver;
+use tokio::task::JoinSet;
+
+use crate::issuer::{IssueRequest, Issuer};
+use crate::measure::LatencySummary;
+use crate::verifier::Verifier;
+use crate::{Error, Result};
+
+/// Outcome of one [`run`].
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub tasks: usize,
+    /// Credentials issued and verified successfully.
+    pub completed: usize,
+    pub failed: usize,
+    pub elapsed: Duration,
+    /// Issue-plus-verify latency of the completed credentials.
+    pub latency: Option<LatencySummary>,
+}
+
+impl Run {
+    pub fn per_second(&self) -> f64 {
+        self.completed as f64 / self.elapsed.as_secs_f64()
+    }
+
+    /// Throughput relative to `tasks` times the single-task throughput of
+    /// `baseline`: 1.0 is perfect linear scaling.
+    pub fn efficiency(&self, baseline: &Run) -> f64 {
+        self.per_second() / (baseline.per_second() * self.tasks as f64)
+    }
+}
+
+fn request(claims: usize) -> IssueRequest {
+    IssueRequest {
+        subject: "urn:uuid:".to_string() + &uuid::Uuid::new_v4().to_string(),
+        claims: (1..=claims)
+            .map(|i| (format!("claim{}", i), format!("value {}", i).into()))
+            .collect(),
+        template: None,
+    }
+}
+
+/// Spawns `tasks` tasks that each loop issue-then-verify with `claims` claims
+/// until `duration` has passed. Spread over cores by the multi-thread runtime.
+pub async fn run(
+    tasks: usize,
+    duration: Duration,
+    claims: usize,
+    resolver: Arc<dyn DIDResolver + Send>,
+) -> Result<Run> {
+    if tasks == 0 {
+        return Err(Error::Usage("at least one task is needed".to_string()));
+    }
+    let issuer = Arc::new(Issuer::new(
+        crate::issuer_key()?,
+        crate::ISSUER_DID,
+        crate::VERIFICATION_METHOD,
+        resolver.clone(),
+    ));
+    let verifier = Verifier::new(resolver);
+
+    let start = Instant::now();
+    let deadline = start + duration;
+    let mut set = JoinSet::new();
+    for _ in 0..tasks {
+        let issuer = issuer.clone();
+        let verifier = verifier.clone();
+        set.spawn(async move {
+            let mut latencies = Vec::new();
+            let mut failed = 0;
+            while Instant::now() < deadline {
+                let t = Instant::now();
+                let verified = match issuer.issue(&request(claims)).await {
+                    Ok(jwt) => verifier.verify(&jwt).await.verified,
+                    Err(_) => false,
+                };
+                if verified {
+                    latencies.push(t.elapsed());
+                } else {
+                    failed += 1;
+                }
+                // Let the other tasks on this worker in between credentials.
+                tokio::task::yield_now().await;
+            }
+            (latencies, failed)
+        });
+    }
+
+    let mut latencies = Vec::new();
+    let mut failed = 0;
+    while let Some(joined) = set.join_next().await {
+        let (mut task_latencies, task_failed) =
+            joined.map_err(|e| Error::Measurement(e.to_string()))?;
+        latencies.append(&mut task_latencies);
+        failed += task_failed;
+    }
+    let elapsed = start.elapsed();
+    Ok(Run {
+        tasks,
+        completed: latencies.len(),
+        failed,
+        elapsed,
+        latency: LatencySummary::from_samples(latencies),
+    })
+}
@@ -0,0 +1,62 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use didkemb::cli::Args;
+use didkemb::resolver::CachingResolver;
+use didkemb::throughput;
+use didkemb::DIDExampleStatic;
+use ssi_dids::did_resolve::DIDResolver;
+
+fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+// throughput-bench [--tasks K] [--duration-secs 5] [--claims 10] [--cache-ttl-secs N]
+// Esegue 1..=K task concorrenti (K = num_cpus di default) e stima l'efficienza
+// di scalabilità rispetto a un solo task.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    didkemb::runtime::block_on(run())?
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::from_env();
+    let max_tasks: usize = args.parse_or("tasks", num_cpus::get())?;
+    let duration = Duration::from_secs(args.parse_or("duration-secs", 5)?);
+    let claims: usize = args.parse_or("claims", 10)?;
+    let resolver: Arc<dyn DIDResolver + Send> = match args.value("cache-ttl-secs") {
+        Some(_) => Arc::new(CachingResolver::new(
+            DIDExampleStatic,
+            Duration::from_secs(args.parse_or("cache-ttl-secs", 0)?),
+            64,
+        )),
+        None => Arc::new(DIDExampleStatic),
+    };
+    eprintln!(
+        "{} logical cores, {} physical",
+        num_cpus::get(),
+        num_cpus::get_physical()
+    );
+
+    println!("tasks,credentials,failed,per_second,p50_ms,p95_ms,p99_ms,max_ms,efficiency");
+    let mut baseline = None;
+    for tasks in 1..=max_tasks {
+        let run = throughput::run(tasks, duration, claims, resolver.clone()).await?;
+        let baseline = baseline.get_or_insert_with(|| run.clone());
+        let (p50, p95, p99, max) = run.latency.map_or((0.0, 0.0, 0.0, 0.0), |l| {
+            (ms(l.p50), ms(l.p95), ms(l.p99), ms(l.max))
+        });
+        println!(
+            "{},{},{},{:.1},{:.3},{:.3},{:.3},{:.3},{:.2}",
+            tasks,
+            run.completed,
+            run.failed,
+            run.per_second(),
+            p50,
+            p95,
+            p99,
+            max,
+            run.efficiency(baseline),
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,128 @@
+//! Issuer trust registry: which issuer DIDs may issue which credential types
+//! and claims, and when. Evaluated as a policy once a credential has passed
+//! the cryptographic checks, so any resolvable issuer is no longer enough.
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ssi::vc::Credential;
+
+use crate::{Error, Result};
+
+/// One registry entry. Empty `types` or absent `claims` allow any.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustRule {
+    /// Name reported with every decision.
+    pub id: String,
+    pub issuer: String,
+    /// Credential types besides `VerifiableCredential`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub types: Vec<String>,
+    /// `credentialSubject` properties besides `id`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claims: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_from: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<DateTime<Utc>>,
+}
+
+impl TrustRule {
+    /// Why the rule does not accept `vc`, issued at `issued` and verified at
+    /// `now`, if it does not. The rule must be in effect at both, so an
+    /// issuer cannot backdate credentials into an expired rule.
+    fn reject(&self, vc: &Credential, issued: DateTime<Utc>, now: DateTime<Utc>) -> Option<String> {
+        match (self.valid_from, self.valid_until) {
+            (Some(from), _) if issued.min(now) < from => {
+                return Some(format!("not in effect before {}", from));
+            }
+            (_, Some(until)) if issued.max(now) > until => {
+                return Some(format!("expired at {}", until))
+            }
+            _ => {}
+        }
+        if !self.types.is_empty() {
+            let denied = (&vc.type_)
+                .into_iter()
+                .find(|t| *t != "VerifiableCredential" && !self.types.contains(t));
+            if let Some(denied) = denied {
+                return Some(format!("type {} not allowed", denied));
+            }
+        }
+        if let Some(ref claims) = self.claims {
+            for subject in &vc.credential_subject {
+                let mut properties = subject.property_set.iter().flat_map(|p| p.keys());
+                if let Some(denied) = properties.find(|p| !claims.contains(p)) {
+                    return Some(format!("claim {} not allowed", denied));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Outcome of [`TrustRegistry::evaluate`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyDecision {
+    pub accepted: bool,
+    /// The rule that accepted the credential, or the first that rejected it;
+    /// `None` when the registry has no rule for the issuer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    /// Why every rule for the issuer rejected the credential.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustRegistry {
+    pub rules: Vec<TrustRule>,
+}
+
+impl TrustRegistry {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let json = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| Error::Encoding(format!("{}: {}", path.as_ref().display(), e)))?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Accepts `vc` if one of its issuer's rules does, checking the rule's
+    /// validity period against both `issuanceDate` and now.
+    pub fn evaluate(&self, vc: &Credential) -> PolicyDecision {
+        let issuer = vc.issuer.as_ref().map(|i| i.get_id()).unwrap_or_default();
+        let now = Utc::now();
+        let issued = vc
+            .issuance_date
+            .clone()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or(now);
+        let mut rejections = Vec::new();
+        for rule in self.rules.iter().filter(|r| r.issuer == issuer) {
+            match rule.reject(vc, issued, now) {
+                None => {
+                    return PolicyDecision {
+                        accepted: true,
+                        rule: Some(rule.id.clone()),
+                        reason: None,
+                    }
+                }
+                Some(reason) => rejections.push((rule.id.clone(), reason)),
+            }
+        }
+        let reason = match rejections.is_empty() {
+            true => format!("No trust rule for {}", issuer),
+            false => rejections
+                .iter()
+                .map(|(rule, reason)| format!("{}: {}", rule, reason))
+                .collect::<Vec<_>>()
+                .join("; "),
+        };
+        PolicyDecision {
+            accepted: false,
+            rule: rejections.into_iter().next().map(|(rule, _)| rule),
+            reason: Some(reason),
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Batch verification of JWT credentials and presentations, as served by the
+//! `verifier-server` binary.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use hyper::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ssi::jsonld::ContextLoader;
+use ssi_dids::did_resolve::DIDResolver;
+use tokio::task::JoinSet;
+
+use crate::chain::{ChainRequest, ChainVerifier};
+use crate::http::{error_body, Routes};
+use crate::report::{self, VerificationReport};
+use crate::schema::SchemaRegistry;
+use crate::trust::TrustRegistry;
+
+/// Body of `POST /verify`: a single JWT or a batch.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum VerifyRequest {
+    One { jwt: String },
+    Batch { jwts: Vec<String> },
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BatchResponse {
+    /// Whether every item verified.
+    pub verified: bool,
+    pub results: Vec<VerificationReport>,
+}
+
+#[derive(Clone)]
+struct Inner {
+    resolver: Arc<dyn DIDResolver + Send>,
+    context_loader: ContextLoader,
+    registry: Option<TrustRegistry>,
+    schemas: Option<SchemaRegistry>,
+    chain: Option<ChainVerifier>,
+}
+
+/// Cheap to clone; clones share the resolver and context loader.
+#[derive(Clone)]
+pub struct Verifier {
+    inner: Arc<Inner>,
+}
+
+impl Verifier {
+    pub fn new(resolver: Arc<dyn DIDResolver + Send>) -> Self {
+        Self::with_context_loader(resolver, crate::context::loader())
+    }
+
+    pub fn with_context_loader(
+        resolver: Arc<dyn DIDResolver + Send>,
+        context_loader: ContextLoader,
+    ) -> Self {
+        Verifier {
+            inner: Arc::new(Inner {
+                resolver,
+                context_loader,
+                registry: None,
+                schemas: None,
+                chain: None,
+            }),
+        }
+    }
+
+    fn configure(self, f: impl FnOnce(&mut Inner)) -> Self {
+        let mut inner = Arc::try_unwrap(self.inner).unwrap_or_else(|inner| (*inner).clone());
+        f(&mut inner);
+        Verifier {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// Also requires every credential to be accepted by `registry`.
+    pub fn with_trust_registry(self, registry: TrustRegistry) -> Self {
+        self.configure(|inner| inner.registry = Some(registry))
+    }
+
+    /// Also validates credentials naming a `credentialSchema` against
+    /// `schemas`.
+    pub fn with_schemas(self, schemas: SchemaRegistry) -> Self {
+        self.configure(|inner| inner.schemas = Some(schemas))
+    }
+
+    /// Serves `POST /verify-chain`, walking accreditations up to `roots`.
+    pub fn with_roots(self, roots: Vec<String>, max_depth: usize) -> Self {
+        self.configure(|inner| {
+            inner.chain = Some(
+                ChainVerifier::new(roots, inner.resolver.clone())
+                    .with_context_loader(inner.context_loader.clone())
+                    .max_depth(max_depth),
+            )
+        })
+    }
+
+    /// Verifies one JWT credential or presentation.
+    pub async fn verify(&self, jwt: &str) -> VerificationReport {
+        let mut context_loader = self.inner.context_loader.clone();
+        let resolver = self.inner.resolver.as_ref();
+        let mut report = report::verify_any_jwt(jwt, None, resolver, &mut context_loader).await;
+        if let Some(ref schemas) = self.inner.schemas {
+            report::check_schemas(&mut report, jwt, schemas);
+        }
+        if let Some(ref registry) = self.inner.registry {
+            report::check_trust(&mut report, jwt, registry);
+        }
+        report
+    }
+
+    /// Verifies every JWT on its own task; results keep the input order.
+    pub async fn verify_batch(&self, jwts: Vec<String>) -> Vec<VerificationReport> {
+        let mut tasks = JoinSet::new();
+        for (i, jwt) in jwts.into_iter().enumerate() {
+            let verifier = self.clone();
+            tasks.spawn(async move { (i, verifier.verify(&jwt).await) });
+        }
+        let mut results = vec![VerificationReport::default(); tasks.len()];
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((i, report)) => results[i] = report,
+                Err(e) => eprintln!("verification task failed: {}", e),
+            }
+        }
+        results
+    }
+}
+
+#[async_trait]
+impl Routes for Verifier {
+    async fn handle(&self, method: &Method, path: &str, body: &[u8]) -> (StatusCode, Value) {
+        match (method, path) {
+            (&Method::POST, "/verify") => match serde_json::from_slice(body) {
+                Ok(VerifyRequest::One { jwt }) => {
+                    (StatusCode::OK, serde_json::json!(self.verify(&jwt).await))
+                }
+                Ok(VerifyRequest::Batch { jwts }) => {
+                    let results = self.verify_batch(jwts).await;
+                    let response = BatchResponse {
+                        verified: results.iter().all(|r| r.verified),
+                        results,
+                    };
+                    (StatusCode::OK, serde_json::json!(response))
+                }
+                Err(e) => (StatusCode::BAD_REQUEST, error_body(e)),
+            },
+            (&Method::POST, "/verify-chain") => {
+                let Some(ref chain) = self.inner.chain else {
+                    return (
+                        StatusCode::NOT_FOUND,
+                        error_body("No root of trust configured"),
+                    );
+                };
+                match serde_json::from_slice::<ChainRequest>(body) {
+                    Ok(req) => (
+                        StatusCode::OK,
+                        serde_json::json!(chain.verify(&req.jwt, &req.accreditations).await),
+                    ),
+                    Err(e) => (StatusCode::BAD_REQUEST, error_body(e)),
+                }
+            }
+            _ => (StatusCode::NOT_FOUND, error_body("Not found")),
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use didkemb::chain;
+use didkemb::cli::Args;
+use didkemb::resolver;
+use didkemb::schema::SchemaRegistry;
+use didkemb::trust::TrustRegistry;
+use didkemb::verifier::Verifier;
+
+// verifier-server [--addr 127.0.0.1:8081] [--trust registro.json]
+//                 [--roots DID,DID [--max-depth 3]] [--schemas schemas/]
+// Con --schemas i soggetti delle credenziali con credentialSchema vengono
+// validati con gli schemi locali. Con --trust ogni credenziale deve essere accettata da una regola del
+// registro degli emittenti fidati. Con --roots è attivo anche
+// POST /verify-chain, che risale gli accreditamenti fino a una radice.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    didkemb::runtime::block_on(run())?
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::from_env();
+    let addr: SocketAddr = args.parse_or("addr", SocketAddr::from(([127, 0, 0, 1], 8081)))?;
+    let mut verifier = Verifier::new(Arc::new(resolver::default_chain()));
+    if let Some(dir) = args.value("schemas") {
+        verifier = verifier.with_schemas(SchemaRegistry::from_dir(dir)?);
+    }
+    if let Some(path) = args.value("trust") {
+        verifier = verifier.with_trust_registry(TrustRegistry::load(path)?);
+    }
+    if let Some(roots) = args.value("roots") {
+        let max_depth = args.parse_or("max-depth", chain::DEFAULT_MAX_DEPTH)?;
+        let roots = roots.split(',').map(str::to_string).collect();
+        verifier = verifier.with_roots(roots, max_depth);
+    }
+
+    let (addr, server) = didkemb::http::serve(addr, verifier)?;
+    eprintln!("verifier-server listening on http://{}", addr);
+    server.await?;
+    Ok(())
+}
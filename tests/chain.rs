@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use didkemb::chain::{self, ChainReport, ChainVerifier, ACCREDITATION};
+use didkemb::fuzz;
+use didkemb::http::Routes;
+use didkemb::identity::{Identity, Method};
+use didkemb::resolver;
+use didkemb::verifier::Verifier;
+use hyper::{Method as HttpMethod, StatusCode};
+use serde_json::json;
+use ssi::one_or_many::OneOrMany;
+
+fn fixture(name: &str) -> String {
+    let path = format!(
+        "{}/tests/fixtures/chain/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    );
+    std::fs::read_to_string(path).unwrap().trim().to_string()
+}
+
+fn identity(name: &str) -> Identity {
+    let key = serde_json::from_str(&fixture(&format!("{}.json", name))).unwrap();
+    Identity::from_key(key, Method::Key).unwrap()
+}
+
+fn device_credential(credential_type: &str) -> String {
+    let gateway = identity("gateway");
+    let mut vc = didkemb::example_credential(3).unwrap();
+    vc.issuer = Some(ssi::vc::Issuer::URI(ssi::vc::URI::String(
+        gateway.did.clone(),
+    )));
+    vc.type_ = OneOrMany::Many(vec![
+        "VerifiableCredential".to_string(),
+        credential_type.to_string(),
+    ]);
+    fuzz::sign_as(&vc, &gateway.key, &gateway.assertion_method).unwrap()
+}
+
+fn verifier(root: &str) -> ChainVerifier {
+    ChainVerifier::new(vec![root.to_string()], Arc::new(resolver::default_chain()))
+}
+
+/// `root` accrediting the manufacturer for `types`, before the manufacturer
+/// accredited the gateway.
+fn manufacturer_accreditation(root: &Identity, types: &[&str]) -> String {
+    let mut vc =
+        chain::accreditation(&root.did, &identity("manufacturer").did, types, None).unwrap();
+    vc.issuance_date = Some("2023-01-01T00:00:00Z".parse().unwrap());
+    fuzz::sign_as(&vc, &root.key, &root.assertion_method).unwrap()
+}
+
+#[tokio::test]
+async fn chain_reaches_the_root_of_trust() {
+    let manufacturer = identity("manufacturer");
+    let report = verifier(&manufacturer.did)
+        .verify(
+            &device_credential("DeviceCredential"),
+            &[fixture("accreditation.jwt")],
+        )
+        .await;
+    assert!(report.verified, "{}", report);
+    assert_eq!(report.root, Some(manufacturer.did));
+    assert_eq!(report.links.len(), 2);
+    assert!(report.links.iter().all(|link| link.verified));
+}
+
+#[tokio::test]
+async fn depth_is_limited() {
+    let root = Identity::generate(Method::Key).unwrap();
+    let accreditations = [
+        fixture("accreditation.jwt"),
+        manufacturer_accreditation(&root, &[ACCREDITATION, "DeviceCredential"]),
+    ];
+    let device = device_credential("DeviceCredential");
+
+    let report = verifier(&root.did).verify(&device, &accreditations).await;
+    assert!(report.verified, "{}", report);
+    assert_eq!(report.links.len(), 3);
+
+    let report = verifier(&root.did)
+        .max_depth(1)
+        .verify(&device, &accreditations)
+        .await;
+    assert!(!report.verified);
+    assert!(report.to_string().contains("within 1"), "{}", report);
+}
+
+#[tokio::test]
+async fn types_must_be_accredited() {
+    let manufacturer = identity("manufacturer");
+    let report = verifier(&manufacturer.did)
+        .verify(
+            &device_credential("FirmwareAttestation"),
+            &[fixture("accreditation.jwt")],
+        )
+        .await;
+    assert!(!report.verified);
+    assert!(
+        report
+            .to_string()
+            .contains("not accredited to issue FirmwareAttestation"),
+        "{}",
+        report
+    );
+
+    // Accrediting others needs AccreditationCredential among the types.
+    let root = Identity::generate(Method::Key).unwrap();
+    let accreditations = [
+        fixture("accreditation.jwt"),
+        manufacturer_accreditation(&root, &["DeviceCredential"]),
+    ];
+    let report = verifier(&root.did)
+        .verify(&device_credential("DeviceCredential"), &accreditations)
+        .await;
+    assert!(!report.verified);
+    assert!(
+        report.to_string().contains(&format!(
+            "{} is not accredited to issue {}",
+            manufacturer.did, ACCREDITATION
+        )),
+        "{}",
+        report
+    );
+}
+
+#[tokio::test]
+async fn broken_chains_are_rejected() {
+    let manufacturer = identity("manufacturer");
+    let gateway = identity("gateway");
+    let device = device_credential("DeviceCredential");
+
+    let report = verifier(&manufacturer.did).verify(&device, &[]).await;
+    assert!(!report.verified);
+    assert_eq!(
+        report.reason,
+        Some(format!("No accreditation for {}", gateway.did))
+    );
+
+    // Accredited by someone outside the chain of trust.
+    let report = verifier(&manufacturer.did)
+        .verify(&device, &[fixture("rogue-accreditation.jwt")])
+        .await;
+    assert!(!report.verified);
+    assert_eq!(
+        report.reason,
+        Some(format!("No accreditation for {}", identity("rogue").did))
+    );
+
+    // Expired before the device credential was issued.
+    let report = verifier(&manufacturer.did)
+        .verify(&device, &[fixture("expired-accreditation.jwt")])
+        .await;
+    assert!(!report.verified);
+    assert!(report.to_string().contains("not in effect"), "{}", report);
+}
+
+#[tokio::test]
+async fn verifier_server_walks_the_chain() {
+    let manufacturer = identity("manufacturer");
+    let verifier = Verifier::new(Arc::new(resolver::default_chain()))
+        .with_roots(vec![manufacturer.did.clone()], chain::DEFAULT_MAX_DEPTH);
+    let body = json!({
+        "jwt": device_credential("DeviceCredential"),
+        "accreditations": [fixture("accreditation.jwt")],
+    });
+    let (status, response) = verifier
+        .handle(
+            &HttpMethod::POST,
+            "/verify-chain",
+            body.to_string().as_bytes(),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let report: ChainReport = serde_json::from_value(response).unwrap();
+    assert!(report.verified, "{}", report);
+    assert_eq!(report.root, Some(manufacturer.did));
+
+    let (status, _) = Verifier::new(Arc::new(resolver::default_chain()))
+        .handle(
+            &HttpMethod::POST,
+            "/verify-chain",
+            body.to_string().as_bytes(),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
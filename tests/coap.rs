@@ -0,0 +1,135 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use coap_lite::{CoapRequest, MessageClass, MessageType, Packet, RequestType, ResponseType};
+use didkemb::coap::{self, CoapClient, CoapServer, MAX_RESPONSES};
+use didkemb::fuzz::Tamper;
+use didkemb::issuer::{IssueRequest, Issuer};
+use didkemb::verifier::Verifier;
+use didkemb::DIDExampleStatic;
+use tokio::net::UdpSocket;
+
+async fn start(max_message_size: usize) -> SocketAddr {
+    let issuer = Issuer::new(
+        didkemb::issuer_key().unwrap(),
+        didkemb::ISSUER_DID,
+        didkemb::VERIFICATION_METHOD,
+        Arc::new(DIDExampleStatic),
+    );
+    let verifier = Verifier::new(Arc::new(DIDExampleStatic));
+    let server = CoapServer::new(issuer, verifier, max_message_size);
+    let (addr, server) = coap::serve(([127, 0, 0, 1], 0).into(), server)
+        .await
+        .unwrap();
+    tokio::spawn(server);
+    addr
+}
+
+fn request(claims: usize) -> IssueRequest {
+    IssueRequest {
+        subject: "did:example:device1".to_string(),
+        claims: (1..=claims)
+            .map(|i| (format!("claim{}", i), format!("value {}", i).into()))
+            .collect(),
+        template: None,
+    }
+}
+
+#[tokio::test]
+async fn issue_and_present_blockwise() {
+    let addr = start(128).await;
+    let client = CoapClient::connect(addr, 64).await.unwrap();
+
+    let (jwt, issued) = client.issue(&request(20)).await.unwrap();
+    assert!(issued.blocks_sent > 1);
+    assert_eq!(issued.blocks_received, jwt.len().div_ceil(64));
+
+    let (report, presented) = client.present(&jwt).await.unwrap();
+    assert!(report.verified, "{}", report);
+    assert_eq!(presented.blocks_sent, jwt.len().div_ceil(64));
+    assert!(presented.blocks_received > 1);
+    assert_eq!(issued.retransmissions + presented.retransmissions, 0);
+}
+
+#[tokio::test]
+async fn single_datagram_when_it_fits() {
+    let addr = start(1152).await;
+    let client = CoapClient::connect(addr, 1024).await.unwrap();
+    let (jwt, issued) = client.issue(&request(1)).await.unwrap();
+    assert_eq!((issued.blocks_sent, issued.blocks_received), (1, 1));
+    let (report, _) = client.present(&jwt).await.unwrap();
+    assert!(report.verified, "{}", report);
+}
+
+#[tokio::test]
+async fn tampered_credential_is_reported() {
+    let addr = start(256).await;
+    let client = CoapClient::connect(addr, 128).await.unwrap();
+    let vc = didkemb::example_credential(10).unwrap();
+    let jwt = Tamper::WrongKey
+        .apply(&vc, &didkemb::issuer_key().unwrap())
+        .await
+        .unwrap();
+    let (report, _) = client.present(&jwt).await.unwrap();
+    assert!(!report.verified);
+}
+
+#[tokio::test]
+async fn errors_map_to_response_codes() {
+    let addr = start(1152).await;
+    let client = CoapClient::connect(addr, 256).await.unwrap();
+    let exchange = client.post("nope", b"").await.unwrap();
+    assert_eq!(exchange.status, ResponseType::NotFound);
+    let exchange = client.post("issue", b"{}").await.unwrap();
+    assert_eq!(exchange.status, ResponseType::BadRequest);
+    assert!(CoapClient::connect(addr, 100).await.is_err());
+}
+
+#[tokio::test]
+async fn retransmissions_are_answered_without_issuing_again() {
+    let addr = start(1152).await;
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let datagram = |message_id: u16| {
+        let mut post: CoapRequest<SocketAddr> = CoapRequest::new();
+        post.set_method(RequestType::Post);
+        post.set_path(match message_id {
+            7 | 8 => "issue",
+            _ => "nothing",
+        });
+        let mut packet = post.message;
+        packet.header.set_type(MessageType::Confirmable);
+        packet.header.message_id = message_id;
+        packet.set_token(vec![1, 2, 3, 4]);
+        packet.payload = serde_json::to_vec(&request(1)).unwrap();
+        packet.to_bytes().unwrap()
+    };
+    let exchange = |message_id: u16| {
+        let socket = &socket;
+        async move {
+            socket.send_to(&datagram(message_id), addr).await.unwrap();
+            let mut buf = [0u8; 2048];
+            let (len, _) = socket.recv_from(&mut buf).await.unwrap();
+            let reply = Packet::from_bytes(&buf[..len]).unwrap();
+            assert_eq!(reply.header.message_id, message_id);
+            reply
+        }
+    };
+    let mut replies = Vec::new();
+    for message_id in [7, 7, 8] {
+        let reply = exchange(message_id).await;
+        assert_eq!(
+            reply.header.code,
+            MessageClass::Response(ResponseType::Content)
+        );
+        replies.push(reply.payload);
+    }
+    // The JWTs differ in their issuance date unless the first was resent.
+    assert_eq!(replies[0], replies[1]);
+    assert_ne!(replies[0], replies[2]);
+
+    // Once MAX_RESPONSES newer exchanges are kept, the first is forgotten.
+    for message_id in 100..100 + MAX_RESPONSES as u16 {
+        exchange(message_id).await;
+    }
+    assert_ne!(exchange(7).await.payload, replies[0]);
+}
@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use didkemb::fuzz;
+use didkemb::issuer::{IssueRequest, Issuer};
+use didkemb::report::{CheckKind, Outcome, VerificationReport};
+use didkemb::schema::{self, SchemaRegistry};
+use didkemb::template::CredentialTemplate;
+use didkemb::verifier::Verifier;
+use didkemb::{DIDExampleStatic, Error};
+use serde_json::json;
+
+const BENCHMARK_CLAIMS: &str = "https://didkemb.example/schemas/benchmark-claims.json";
+
+fn schemas() -> SchemaRegistry {
+    SchemaRegistry::from_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/schemas")).unwrap()
+}
+
+fn issuer() -> Issuer {
+    Issuer::new(
+        didkemb::issuer_key().unwrap(),
+        didkemb::ISSUER_DID,
+        didkemb::VERIFICATION_METHOD,
+        Arc::new(DIDExampleStatic),
+    )
+    .with_templates(CredentialTemplate::load_dir("templates").unwrap())
+    .with_schemas(schemas())
+}
+
+fn reading(value: &str) -> IssueRequest {
+    serde_json::from_value(json!({
+        "template": "sensor-reading",
+        "subject": "did:example:device1",
+        "claims": { "reading": value },
+    }))
+    .unwrap()
+}
+
+fn verifier() -> Verifier {
+    Verifier::new(Arc::new(DIDExampleStatic)).with_schemas(schemas())
+}
+
+fn schema_check(report: &VerificationReport) -> (Outcome, Option<String>) {
+    let check = report.get(CheckKind::Schema).unwrap();
+    (check.outcome, check.reason.clone())
+}
+
+#[tokio::test]
+async fn valid_subject_is_issued_and_verified() {
+    let jwt = issuer().issue(&reading("21.5")).await.unwrap();
+    let report = verifier().verify(&jwt).await;
+    assert!(report.verified, "{}", report);
+    assert_eq!(schema_check(&report).0, Outcome::Passed);
+}
+
+#[tokio::test]
+async fn invalid_subject_is_not_signed() {
+    let Err(Error::Schema(errors)) = issuer().issue(&reading("warm")).await else {
+        panic!("issued a credential with an invalid reading");
+    };
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].pointer, "/credentialSubject/reading");
+
+    let (status, body) = didkemb::http::Routes::handle(
+        &issuer(),
+        &hyper::Method::POST,
+        "/credentials/issue",
+        serde_json::to_string(&reading("warm")).unwrap().as_bytes(),
+    )
+    .await;
+    assert_eq!(status, hyper::StatusCode::BAD_REQUEST);
+    assert_eq!(body["errors"][0]["pointer"], "/credentialSubject/reading");
+}
+
+#[tokio::test]
+async fn verification_revalidates_the_subject() {
+    let mut vc = didkemb::example_credential(3).unwrap();
+    schema::attach(&mut vc, BENCHMARK_CLAIMS);
+    let key = didkemb::issuer_key().unwrap();
+    let report = verifier()
+        .verify(&fuzz::sign(&vc, &key).await.unwrap())
+        .await;
+    assert!(report.verified, "{}", report);
+
+    // Signed without validation: claim2 is not a string.
+    let mut subject = serde_json::to_value(&vc.credential_subject).unwrap();
+    subject["claim2"] = json!(2);
+    vc.credential_subject = serde_json::from_value(subject).unwrap();
+    let report = verifier()
+        .verify(&fuzz::sign(&vc, &key).await.unwrap())
+        .await;
+    assert!(!report.verified);
+    let (outcome, reason) = schema_check(&report);
+    assert_eq!(outcome, Outcome::Failed);
+    assert!(
+        reason.unwrap().starts_with("/credentialSubject/claim2: "),
+        "{}",
+        report
+    );
+}
+
+#[tokio::test]
+async fn unknown_or_missing_schema() {
+    let key = didkemb::issuer_key().unwrap();
+    let mut vc = didkemb::example_credential(1).unwrap();
+    let report = verifier()
+        .verify(&fuzz::sign(&vc, &key).await.unwrap())
+        .await;
+    assert!(report.verified, "{}", report);
+    assert_eq!(schema_check(&report).0, Outcome::Skipped);
+
+    schema::attach(&mut vc, "https://didkemb.example/schemas/nope.json");
+    let report = verifier()
+        .verify(&fuzz::sign(&vc, &key).await.unwrap())
+        .await;
+    let (outcome, reason) = schema_check(&report);
+    assert_eq!(outcome, Outcome::Failed);
+    assert_eq!(
+        reason.as_deref(),
+        Some("/credentialSchema: Unknown schema https://didkemb.example/schemas/nope.json")
+    );
+}
+
+#[test]
+fn draft_2020_12_keywords_are_enforced() {
+    let mut registry = SchemaRegistry::new();
+    let id = registry
+        .insert(json!({
+            "$id": "https://didkemb.example/schemas/hash-only.json",
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": { "id": { "type": "string" }, "hash": { "type": "string" } },
+            "unevaluatedProperties": false
+        }))
+        .unwrap();
+    let mut vc = didkemb::example_credential(0).unwrap();
+    schema::attach(&mut vc, &id);
+    assert_eq!(registry.validate(&vc), Ok(()));
+
+    let mut vc = didkemb::example_credential(1).unwrap();
+    schema::attach(&mut vc, &id);
+    let errors = registry.validate(&vc).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].message.contains("claim1"), "{}", errors[0]);
+    // Validated again with the schema compiled on the first call.
+    assert_eq!(registry.validate(&vc).unwrap_err(), errors);
+}
@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use chrono::{Duration, SecondsFormat, Utc};
+use didkemb::datamodel::{self, DataModel, CREDENTIALS_V2, VC_JWT};
+use didkemb::issuer::{IssueRequest, Issuer};
+use didkemb::report::{self, CheckKind, Outcome, VerificationReport};
+use didkemb::schema::SchemaRegistry;
+use didkemb::template::{CredentialTemplate, Variables};
+use didkemb::verifier::Verifier;
+use didkemb::DIDExampleStatic;
+use serde_json::{json, Value};
+
+fn benchmark(model: DataModel) -> Value {
+    CredentialTemplate::benchmark()
+        .render_as(
+            &Variables::from([("claims".to_string(), "10".to_string())]),
+            model,
+        )
+        .unwrap()
+}
+
+fn issue(vc: &Value) -> String {
+    datamodel::issue_vc_jwt(
+        vc,
+        &didkemb::issuer_key().unwrap(),
+        &didkemb::proof_options(),
+    )
+    .unwrap()
+}
+
+async fn verify(jwt: &str) -> VerificationReport {
+    report::verify_jwt(
+        jwt,
+        None,
+        &DIDExampleStatic,
+        &mut didkemb::context::loader(),
+    )
+    .await
+}
+
+fn date(offset: Duration) -> Value {
+    (Utc::now() + offset)
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+        .into()
+}
+
+#[test]
+fn v2_rendering_uses_the_v2_context_and_validity_period() {
+    let vc = CredentialTemplate::load("templates/firmware-attestation.toml")
+        .unwrap()
+        .render_as(
+            &Variables::from([
+                ("subject".to_string(), "did:example:thermo1".to_string()),
+                ("version".to_string(), "2.4.1".to_string()),
+            ]),
+            DataModel::V2,
+        )
+        .unwrap();
+    assert_eq!(
+        vc["@context"],
+        json!([CREDENTIALS_V2, didkemb::context::IOT_V1])
+    );
+    assert!(vc["validFrom"].is_string());
+    assert!(vc["validUntil"].is_string());
+    assert!(vc.get("issuanceDate").is_none());
+    assert!(vc.get("expirationDate").is_none());
+    assert_eq!("2.0".parse::<DataModel>().unwrap(), DataModel::V2);
+}
+
+#[tokio::test]
+async fn vc_jwt_envelope_verifies() {
+    let jwt = issue(&benchmark(DataModel::V2));
+    let (header, payload) = ssi::jws::decode_unverified(&jwt).unwrap();
+    assert_eq!(header.type_.as_deref(), Some(VC_JWT));
+    let payload: Value = serde_json::from_slice(&payload).unwrap();
+    assert_eq!(payload["@context"][0], json!(CREDENTIALS_V2));
+    assert!(payload.get("vc").is_none());
+
+    let report = verify(&jwt).await;
+    assert!(report.verified, "{}", report);
+    assert_eq!(report.issuer.as_deref(), Some(didkemb::ISSUER_DID));
+    let decoded = datamodel::decode_jwt(&jwt, &didkemb::issuer_key().unwrap()).unwrap();
+    assert_eq!(decoded, payload);
+
+    let v1 = DataModel::V1
+        .issue(
+            &benchmark(DataModel::V1),
+            &didkemb::issuer_key().unwrap(),
+            &didkemb::proof_options(),
+            &DIDExampleStatic,
+        )
+        .await
+        .unwrap();
+    assert!(verify(&v1).await.verified);
+    assert!(jwt.len() < v1.len());
+}
+
+#[tokio::test]
+async fn validity_period_is_enforced() {
+    let mut vc = benchmark(DataModel::V2);
+    vc["validUntil"] = date(-Duration::hours(1));
+    let report = verify(&issue(&vc)).await;
+    assert_eq!(
+        report.get(CheckKind::Dates).unwrap().outcome,
+        Outcome::Failed
+    );
+
+    let mut vc = benchmark(DataModel::V2);
+    vc["validFrom"] = date(Duration::hours(1));
+    let report = verify(&issue(&vc)).await;
+    let dates = report.get(CheckKind::Dates).unwrap();
+    assert_eq!(dates.outcome, Outcome::Failed);
+    assert!(dates.reason.as_ref().unwrap().starts_with("validFrom"));
+}
+
+#[tokio::test]
+async fn envelope_must_carry_a_v2_credential() {
+    let v1 = benchmark(DataModel::V1);
+    assert!(datamodel::issue_vc_jwt(
+        &v1,
+        &didkemb::issuer_key().unwrap(),
+        &didkemb::proof_options()
+    )
+    .is_err());
+
+    let header = ssi::jws::Header {
+        algorithm: ssi::jwk::Algorithm::EdDSA,
+        key_id: Some(didkemb::VERIFICATION_METHOD.to_string()),
+        type_: Some(VC_JWT.to_string()),
+        ..Default::default()
+    };
+    let jwt = ssi::jws::encode_sign_custom_header(
+        &v1.to_string(),
+        &didkemb::issuer_key().unwrap(),
+        &header,
+    )
+    .unwrap();
+    let report = verify(&jwt).await;
+    assert_eq!(
+        report.get(CheckKind::Format).unwrap().outcome,
+        Outcome::Failed
+    );
+}
+
+#[tokio::test]
+async fn issuer_and_verifier_handle_v2_with_schemas() {
+    let schemas = SchemaRegistry::from_dir("schemas").unwrap();
+    let issuer = Issuer::new(
+        didkemb::issuer_key().unwrap(),
+        didkemb::ISSUER_DID,
+        didkemb::VERIFICATION_METHOD,
+        Arc::new(DIDExampleStatic),
+    )
+    .with_templates(CredentialTemplate::load_dir("templates").unwrap())
+    .with_schemas(schemas.clone())
+    .with_data_model(DataModel::V2);
+    let req: IssueRequest = serde_json::from_value(json!({
+        "template": "sensor-reading",
+        "subject": "did:example:device1",
+        "claims": { "reading": "21.5" },
+    }))
+    .unwrap();
+    let jwt = issuer.issue(&req).await.unwrap();
+    assert!(datamodel::is_envelope(&jwt));
+
+    let report = Verifier::new(Arc::new(DIDExampleStatic))
+        .with_schemas(schemas)
+        .verify(&jwt)
+        .await;
+    assert!(report.verified, "{}", report);
+    assert_eq!(
+        report.get(CheckKind::Schema).unwrap().outcome,
+        Outcome::Passed
+    );
+}
@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use didkemb::document::DocumentBuilder;
+use didkemb::fuzz;
+use didkemb::identity::{Identity, Method};
+use didkemb::report::{CheckKind, Outcome, VerificationReport};
+use didkemb::resolver::{FileResolver, StaticResolver};
+use didkemb::verifier::Verifier;
+use didkemb::ISSUER_DID;
+use ssi::vc::{Credential, URI};
+use ssi_dids::did_resolve::{DIDResolver, ResolutionInputMetadata};
+
+const DECOMMISSIONED: &str = "did:example:decommissioned";
+
+fn fixture() -> String {
+    concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/deactivated/decommissioned.json"
+    )
+    .to_string()
+}
+
+fn outcome(report: &VerificationReport, check: CheckKind) -> Outcome {
+    report.get(check).map(|c| c.outcome).unwrap()
+}
+
+async fn issue(resolver: &StaticResolver) -> String {
+    let vc = didkemb::example_credential(3).unwrap();
+    let key = didkemb::issuer_key().unwrap();
+    didkemb::issue_jwt(&vc, &key, &didkemb::proof_options(), resolver)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn deactivated_issuer_is_refused() {
+    let resolver = Arc::new(StaticResolver::pre_provisioned());
+    let jwt = issue(&resolver).await;
+    let verifier = Verifier::new(resolver.clone());
+    assert!(verifier.verify(&jwt).await.verified);
+
+    assert!(resolver.deactivate(ISSUER_DID));
+    assert!(!resolver.deactivate("did:example:unknown"));
+    let report = verifier.verify(&jwt).await;
+    assert!(!report.verified);
+    assert_eq!(
+        outcome(&report, CheckKind::IssuerResolution),
+        Outcome::Passed
+    );
+    assert_eq!(outcome(&report, CheckKind::IssuerActive), Outcome::Failed);
+    assert!(report.to_string().contains("deactivated"), "{}", report);
+    assert_eq!(outcome(&report, CheckKind::Signature), Outcome::Skipped);
+}
+
+#[tokio::test]
+async fn file_resolver_reports_deactivation() {
+    let resolver = FileResolver::new(fixture());
+    let (_, doc, meta) = resolver
+        .resolve(DECOMMISSIONED, &ResolutionInputMetadata::default())
+        .await;
+    assert_eq!(doc.unwrap().id, DECOMMISSIONED);
+    assert_eq!(meta.unwrap().deactivated, Some(true));
+
+    let mut vc: Credential = didkemb::example_credential(3).unwrap();
+    vc.issuer = Some(ssi::vc::Issuer::URI(URI::String(
+        DECOMMISSIONED.to_string(),
+    )));
+    let kid = format!("{}#key-1", DECOMMISSIONED);
+    let jwt = fuzz::sign_as(&vc, &didkemb::issuer_key().unwrap(), &kid).unwrap();
+    let report = Verifier::new(Arc::new(resolver)).verify(&jwt).await;
+    assert_eq!(outcome(&report, CheckKind::IssuerActive), Outcome::Failed);
+}
+
+#[test]
+fn builder_round_trips_deactivation() {
+    let mut builder =
+        DocumentBuilder::from_json(&std::fs::read_to_string(fixture()).unwrap()).unwrap();
+    assert!(builder.is_deactivated());
+    assert_eq!(builder.id(), DECOMMISSIONED);
+
+    let mut active = DocumentBuilder::new("did:example:device");
+    assert_eq!(active.to_file(), active.build());
+    active.deactivate();
+    let file = active.to_file();
+    assert_eq!(file["didDocumentMetadata"]["deactivated"], true);
+    assert_eq!(file["didDocument"], active.build());
+
+    builder = DocumentBuilder::from_json(&file.to_string()).unwrap();
+    assert!(builder.is_deactivated());
+    assert_eq!(builder.id(), "did:example:device");
+}
+
+#[tokio::test]
+async fn presentation_with_credential_from_deactivated_issuer_fails() {
+    let resolver = Arc::new(StaticResolver::pre_provisioned());
+    let jwt = issue(&resolver).await;
+    let holder = Identity::generate(Method::Key).unwrap();
+    let (_, holder_doc, _) = did_method_key::DIDKey
+        .resolve(&holder.did, &ResolutionInputMetadata::default())
+        .await;
+    resolver.insert(holder_doc.unwrap());
+    let vp = holder.present(vec![jwt], &*resolver).await.unwrap();
+
+    resolver.deactivate(ISSUER_DID);
+    let report = Verifier::new(resolver).verify(&vp).await;
+    assert!(!report.verified);
+    assert_eq!(report.credentials.len(), 1);
+    assert_eq!(
+        outcome(&report.credentials[0], CheckKind::IssuerActive),
+        Outcome::Failed
+    );
+}
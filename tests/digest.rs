@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use didkemb::digest::{HashAlgorithm, PayloadDigest};
+use didkemb::issuer::{IssueRequest, Issuer};
+use didkemb::report::{self, CheckKind, Outcome, VerificationReport};
+use didkemb::DIDExampleStatic;
+
+const PAYLOAD: &[u8] = b"abc";
+
+async fn attest(digest: &PayloadDigest) -> String {
+    let issuer = Issuer::new(
+        didkemb::issuer_key().unwrap(),
+        didkemb::ISSUER_DID,
+        didkemb::VERIFICATION_METHOD,
+        Arc::new(DIDExampleStatic),
+    );
+    let req = IssueRequest {
+        subject: "did:example:device1".to_string(),
+        claims: digest.claims(),
+        template: None,
+    };
+    issuer.issue(&req).await.unwrap()
+}
+
+async fn verify(jwt: &str, payload: &[u8]) -> VerificationReport {
+    let mut context_loader = ssi::jsonld::ContextLoader::default();
+    report::verify_jwt_with_payload(jwt, payload, None, &DIDExampleStatic, &mut context_loader)
+        .await
+}
+
+#[test]
+fn known_digests() {
+    for (algorithm, expected) in [
+        (
+            HashAlgorithm::Sha256,
+            "0xba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        ),
+        (
+            HashAlgorithm::Sha3_256,
+            "0x3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+        ),
+        (
+            HashAlgorithm::Blake3,
+            "0x6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85",
+        ),
+    ] {
+        let digest = PayloadDigest::of_bytes(algorithm, PAYLOAD);
+        assert_eq!(digest.hash, expected, "{}", algorithm);
+        assert_eq!(digest.content_length, 3);
+        assert_eq!(
+            PayloadDigest::of_reader(algorithm, PAYLOAD).unwrap(),
+            digest
+        );
+        assert_eq!(
+            algorithm.name().parse::<HashAlgorithm>().unwrap(),
+            algorithm
+        );
+    }
+    assert!("md5".parse::<HashAlgorithm>().is_err());
+}
+
+#[tokio::test]
+async fn matching_payload_passes() {
+    for algorithm in HashAlgorithm::ALL {
+        let jwt = attest(&PayloadDigest::of_bytes(algorithm, PAYLOAD)).await;
+        let report = verify(&jwt, PAYLOAD).await;
+        assert!(report.verified, "{}", report);
+        assert_eq!(
+            report.get(CheckKind::Payload).unwrap().outcome,
+            Outcome::Passed
+        );
+    }
+}
+
+#[tokio::test]
+async fn altered_payload_fails() {
+    let jwt = attest(&PayloadDigest::of_bytes(HashAlgorithm::Blake3, PAYLOAD)).await;
+
+    let report = verify(&jwt, b"abd").await;
+    assert!(!report.verified);
+    let failure = report.failure().unwrap();
+    assert_eq!(failure.check, CheckKind::Payload);
+    assert!(failure.reason.as_ref().unwrap().contains("does not match"));
+
+    let report = verify(&jwt, b"abcd").await;
+    assert_eq!(report.failure().unwrap().check, CheckKind::Payload);
+}
+
+#[tokio::test]
+async fn credential_without_digest_fails_payload_check() {
+    let jwt = didkemb::fuzz::sign(
+        &didkemb::example_credential(10).unwrap(),
+        &didkemb::issuer_key().unwrap(),
+    )
+    .await
+    .unwrap();
+    let report = verify(&jwt, PAYLOAD).await;
+    assert_eq!(report.failure().unwrap().check, CheckKind::Payload);
+}
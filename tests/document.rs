@@ -0,0 +1,178 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use didkemb::document::{DocumentBuilder, KeyType, Relationship};
+use didkemb::issuer::{IssueRequest, Issuer};
+use didkemb::resolver::FileResolver;
+use didkemb::verifier::Verifier;
+use serde_json::json;
+use ssi::jwk::{Base64urlUInt, OctetParams, Params, JWK};
+
+const DID: &str = "did:example:gateway";
+
+fn x25519() -> JWK {
+    JWK::from(Params::OKP(OctetParams {
+        curve: "X25519".to_string(),
+        public_key: Base64urlUInt(vec![9; 32]),
+        private_key: None,
+    }))
+}
+
+/// A fresh directory under the system temp dir holding `builder`'s document.
+fn publish(name: &str, builder: &DocumentBuilder) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("didkemb-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let json = serde_json::to_string_pretty(&builder.build()).unwrap();
+    std::fs::write(dir.join("gateway.json"), json).unwrap();
+    dir
+}
+
+async fn issue(key: &JWK, vm: &str, dir: &PathBuf) -> String {
+    Issuer::new(key.clone(), DID, vm, Arc::new(FileResolver::new(dir)))
+        .issue(&IssueRequest {
+            subject: "did:example:device1".to_string(),
+            claims: Default::default(),
+            template: None,
+        })
+        .await
+        .unwrap()
+}
+
+async fn verify(jwt: &str, dir: &PathBuf) -> bool {
+    let verifier = Verifier::new(Arc::new(FileResolver::new(dir)));
+    verifier.verify(jwt).await.verified
+}
+
+#[test]
+fn builds_every_key_type_with_its_context() {
+    let signing = JWK::generate_ed25519().unwrap();
+    let mut builder = DocumentBuilder::new(DID);
+    let sign = [Relationship::Authentication, Relationship::AssertionMethod];
+    for (fragment, key_type) in [
+        ("ed", KeyType::Ed25519VerificationKey2020),
+        ("jwk", KeyType::JsonWebKey2020),
+        ("multi", KeyType::Multikey),
+    ] {
+        builder
+            .add_key(fragment, &signing, key_type, &sign)
+            .unwrap();
+    }
+    builder
+        .add_key(
+            "agree",
+            &x25519(),
+            KeyType::Multikey,
+            &[Relationship::KeyAgreement],
+        )
+        .unwrap();
+
+    // Wrong key for the type, or for the relationship.
+    assert!(builder
+        .add_key("bad", &x25519(), KeyType::Ed25519VerificationKey2020, &[])
+        .is_err());
+    assert!(builder
+        .add_key(
+            "bad",
+            &signing,
+            KeyType::Multikey,
+            &[Relationship::KeyAgreement]
+        )
+        .is_err());
+    assert!(builder
+        .add_key("ed", &signing, KeyType::Multikey, &sign)
+        .is_err());
+
+    let doc = builder.build();
+    assert_eq!(
+        doc["@context"],
+        json!([
+            "https://www.w3.org/ns/did/v1",
+            "https://w3id.org/security/suites/ed25519-2020/v1",
+            "https://w3id.org/security/suites/jws-2020/v1",
+            "https://w3id.org/security/multikey/v1",
+        ])
+    );
+    assert_eq!(doc["verificationMethod"].as_array().unwrap().len(), 4);
+    assert_eq!(doc["keyAgreement"], json!([format!("{}#agree", DID)]));
+    assert!(doc["verificationMethod"][1]["publicKeyJwk"]
+        .get("d")
+        .is_none());
+    builder.document().unwrap();
+}
+
+#[tokio::test]
+async fn file_resolver_serves_built_documents() {
+    let key = didkemb::issuer_key().unwrap();
+    for key_type in KeyType::ALL {
+        let mut builder = DocumentBuilder::new(DID);
+        let vm = builder
+            .add_key("key-1", &key, key_type, &[Relationship::AssertionMethod])
+            .unwrap();
+        let dir = publish(key_type.name(), &builder);
+        let jwt = issue(&key, &vm, &dir).await;
+        assert!(verify(&jwt, &dir).await, "{}", key_type);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn rotation_keeps_relationships_and_drops_old_key() {
+    let old = didkemb::issuer_key().unwrap();
+    let new = JWK::generate_ed25519().unwrap();
+    let mut builder = DocumentBuilder::new(DID);
+    let old_vm = builder
+        .add_key(
+            "key-1",
+            &old,
+            KeyType::Ed25519VerificationKey2020,
+            &[Relationship::Authentication, Relationship::AssertionMethod],
+        )
+        .unwrap();
+    let dir = publish("rotation", &builder);
+    let old_jwt = issue(&old, &old_vm, &dir).await;
+    assert!(verify(&old_jwt, &dir).await);
+
+    let new_vm = builder.rotate("key-1", "key-2", &new).unwrap();
+    assert_eq!(
+        builder.relationships_of(&new_vm),
+        vec![Relationship::Authentication, Relationship::AssertionMethod]
+    );
+    assert!(builder.relationships_of(&old_vm).is_empty());
+    assert_eq!(builder.methods().collect::<Vec<_>>(), vec![new_vm.as_str()]);
+
+    publish("rotation", &builder);
+    let new_jwt = issue(&new, &new_vm, &dir).await;
+    assert!(verify(&new_jwt, &dir).await);
+    assert!(!verify(&old_jwt, &dir).await);
+    std::fs::remove_dir_all(dir).unwrap();
+
+    assert!(builder.remove("#key-2"));
+    assert!(!builder.remove("key-2"));
+    assert!(builder.build().get("authentication").is_none());
+}
+
+#[test]
+fn edits_the_hand_written_document() {
+    let mut builder = DocumentBuilder::from_json(include_str!("../did-example-mine.json")).unwrap();
+    assert_eq!(builder.id(), didkemb::ISSUER_DID);
+    builder
+        .add_key(
+            "agree",
+            &x25519(),
+            KeyType::Multikey,
+            &[Relationship::KeyAgreement],
+        )
+        .unwrap();
+    let doc = builder.build();
+    // The inline context is kept and the Multikey one added.
+    assert!(doc["@context"][1].is_object());
+    assert_eq!(
+        doc["@context"][2],
+        json!("https://w3id.org/security/multikey/v1")
+    );
+    assert_eq!(
+        doc["assertionMethod"],
+        json!([didkemb::VERIFICATION_METHOD])
+    );
+    builder.document().unwrap();
+}
@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+
+use didkemb::identity::{Identity, Method};
+use didkemb::issuer::IssueRequest;
+use didkemb::report::Kind;
+use didkemb::resolver::peer::{DIDPeer, Purpose};
+use didkemb::resolver::{self, DIDJWK};
+use didkemb::verifier::Verifier;
+use serde_json::json;
+use ssi::jwk::{Base64urlUInt, OctetParams, Params, JWK};
+use ssi_dids::did_resolve::{DIDResolver, ResolutionInputMetadata, ERROR_INVALID_DID};
+use ssi_dids::{DIDMethod, Document, Source};
+
+async fn resolve(resolver: &dyn DIDResolver, did: &str) -> Result<Document, String> {
+    let (meta, doc, _) = resolver
+        .resolve(did, &ResolutionInputMetadata::default())
+        .await;
+    match (meta.error, doc) {
+        (None, Some(doc)) => Ok(doc),
+        (e, _) => Err(e.unwrap_or_default()),
+    }
+}
+
+fn x25519() -> JWK {
+    JWK::from(Params::OKP(OctetParams {
+        curve: "X25519".to_string(),
+        public_key: Base64urlUInt(vec![7; 32]),
+        private_key: None,
+    }))
+}
+
+#[tokio::test]
+async fn did_jwk_round_trips_public_key_only() {
+    let identity = Identity::generate(Method::Jwk).unwrap();
+    let doc = resolve(&DIDJWK, &identity.did).await.unwrap();
+    let doc = serde_json::to_value(doc).unwrap();
+    assert_eq!(
+        doc["verificationMethod"][0]["id"],
+        json!(identity.assertion_method)
+    );
+    assert_eq!(
+        doc["verificationMethod"][0]["publicKeyJwk"],
+        serde_json::to_value(identity.key.to_public()).unwrap()
+    );
+
+    // A DID embedding the private key is not a valid did:jwk.
+    let leaked = format!(
+        "did:jwk:{}",
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(&identity.key).unwrap())
+    );
+    assert_eq!(
+        resolve(&DIDJWK, &leaked).await.unwrap_err(),
+        ERROR_INVALID_DID
+    );
+}
+
+#[tokio::test]
+async fn did_peer_numalgo0_wraps_the_did_key() {
+    let key = didkemb::issuer_key().unwrap();
+    let did = DIDPeer.generate(&Source::Key(&key)).unwrap();
+    assert_eq!(did, didkemb::ISSUER_DID.replace("did:key:", "did:peer:0"));
+    let doc = resolve(&DIDPeer, &did).await.unwrap();
+    assert_eq!(doc.id, did);
+    assert!(doc.assertion_method.is_some());
+    assert!(doc.key_agreement.is_none());
+}
+
+#[tokio::test]
+async fn did_peer_numalgo2_lists_keys_and_services() {
+    let signing = JWK::generate_ed25519().unwrap();
+    let agreement = x25519();
+    let did = DIDPeer::numalgo2(
+        &[
+            (Purpose::KeyAgreement, &agreement),
+            (Purpose::Authentication, &signing),
+        ],
+        &[json!({"type": "DIDCommMessaging", "serviceEndpoint": "coap://gateway.local"})],
+    )
+    .unwrap();
+    assert!(did.starts_with("did:peer:2.Ez6LS"), "{}", did);
+
+    let doc = serde_json::to_value(resolve(&DIDPeer, &did).await.unwrap()).unwrap();
+    assert_eq!(doc["keyAgreement"], json!([format!("{}#key-1", did)]));
+    assert_eq!(doc["authentication"], json!([format!("{}#key-2", did)]));
+    assert_eq!(doc["service"][0]["id"], json!(format!("{}#service", did)));
+    assert_eq!(doc["service"][0]["type"], json!("DIDCommMessaging"));
+    assert_eq!(
+        doc["service"][0]["serviceEndpoint"],
+        json!("coap://gateway.local")
+    );
+
+    assert!(resolve(&DIDPeer, "did:peer:2.Xz6Mk").await.is_err());
+}
+
+#[tokio::test]
+async fn generated_identities_issue_and_present() {
+    let resolver = Arc::new(resolver::default_chain());
+    let verifier = Verifier::new(resolver.clone());
+    let holder = Identity::generate(Method::Peer2).unwrap();
+    for method in [Method::Key, Method::Jwk, Method::Peer0, Method::Peer2] {
+        let issuer = Identity::generate(method).unwrap();
+        let jwt = issuer
+            .issuer(resolver.clone())
+            .issue(&IssueRequest {
+                subject: holder.did.clone(),
+                claims: Default::default(),
+                template: None,
+            })
+            .await
+            .unwrap();
+        let report = verifier.verify(&jwt).await;
+        assert!(report.verified, "{}: {}", method, report);
+
+        let vp = holder.present(vec![jwt], resolver.as_ref()).await.unwrap();
+        let report = verifier.verify(&vp).await;
+        assert!(report.verified, "{}: {}", method, report);
+        assert_eq!(report.kind, Kind::Presentation);
+        assert_eq!(report.holder.as_deref(), Some(holder.did.as_str()));
+    }
+}
@@ -0,0 +1,107 @@
+use didkemb::context::{self, IOT_VOCAB};
+use didkemb::report;
+use didkemb::template::{CredentialTemplate, Variables};
+use didkemb::DIDExampleStatic;
+use ssi::jsonld::ContextLoader;
+use ssi::ldp::LinkedDataDocument;
+use ssi::rdf::IntoNQuads;
+use ssi::vc::{Credential, LinkedDataProofOptions};
+
+fn ldp_options() -> LinkedDataProofOptions {
+    LinkedDataProofOptions {
+        created: None,
+        ..didkemb::proof_options()
+    }
+}
+
+async fn nquads(vc: &Credential) -> String {
+    vc.to_dataset_for_signing(None, &mut context::loader())
+        .await
+        .unwrap()
+        .into_nquads()
+}
+
+async fn sign_ldp(
+    vc: &Credential,
+    context_loader: &mut ContextLoader,
+) -> didkemb::Result<Credential> {
+    didkemb::issue_ldp(
+        vc,
+        &didkemb::issuer_key().unwrap(),
+        &ldp_options(),
+        &DIDExampleStatic,
+        context_loader,
+    )
+    .await
+}
+
+#[tokio::test]
+async fn claims_expand_into_the_iot_vocabulary() {
+    let vc = didkemb::example_credential(10).unwrap();
+    let nquads = nquads(&vc).await;
+    assert!(nquads.contains(&format!("<{}claim10> \"value 10\"", IOT_VOCAB)));
+    assert!(nquads.contains("<https://schema.org/identifier>"));
+    assert!(!nquads.contains("https://www.w3.org/2018/credentials#claim"));
+}
+
+#[tokio::test]
+async fn sensor_terms_have_their_own_definitions() {
+    let vc = CredentialTemplate::load("templates/sensor-reading.json")
+        .unwrap()
+        .render(&Variables::from([
+            ("subject".to_string(), "did:example:device1".to_string()),
+            ("reading".to_string(), "21.5".to_string()),
+        ]))
+        .unwrap();
+    let mut vc = serde_json::to_value(vc).unwrap();
+    vc["credentialSubject"]["sensor"] = "did:example:device1#thermo".into();
+    vc["credentialSubject"]["timestamp"] = "2026-10-19T08:00:00Z".into();
+    let vc: Credential = serde_json::from_value(vc).unwrap();
+
+    let nquads = nquads(&vc).await;
+    let xsd = "http://www.w3.org/2001/XMLSchema#";
+    for quad in [
+        format!("<{}reading> \"21.5\"^^<{}decimal>", IOT_VOCAB, xsd),
+        format!("<{}unit> \"celsius\"", IOT_VOCAB),
+        format!("<{}sensor> <did:example:device1#thermo>", IOT_VOCAB),
+        format!(
+            "<{}timestamp> \"2026-10-19T08:00:00Z\"^^<{}dateTime>",
+            IOT_VOCAB, xsd
+        ),
+        format!("<{}SensorReading>", IOT_VOCAB),
+    ] {
+        assert!(nquads.contains(&quad), "{} not in\n{}", quad, nquads);
+    }
+}
+
+#[tokio::test]
+async fn ldp_proof_verifies_with_the_offline_loader() {
+    let vc = didkemb::example_credential(10).unwrap();
+    let signed = sign_ldp(&vc, &mut context::loader()).await.unwrap();
+    let report = report::verify_ldp(&signed, &DIDExampleStatic, &mut context::loader()).await;
+    assert!(report.verified, "{}", report);
+
+    // The ssi static loader alone does not know the IoT context.
+    assert!(sign_ldp(&vc, &mut ContextLoader::default()).await.is_err());
+}
+
+#[tokio::test]
+async fn jwt_with_the_iot_context_verifies() {
+    let vc = didkemb::example_credential(10).unwrap();
+    let jwt = didkemb::issue_jwt(
+        &vc,
+        &didkemb::issuer_key().unwrap(),
+        &didkemb::proof_options(),
+        &DIDExampleStatic,
+    )
+    .await
+    .unwrap();
+    let report = report::verify_jwt(&jwt, None, &DIDExampleStatic, &mut context::loader()).await;
+    assert!(report.verified, "{}", report);
+    assert_eq!(
+        didkemb::decode_jwt(&jwt, &didkemb::issuer_key().unwrap())
+            .unwrap()
+            .context,
+        vc.context
+    );
+}
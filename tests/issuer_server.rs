@@ -0,0 +1,135 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use didkemb::issuer::{IssueResponse, Issuer};
+use didkemb::report;
+use didkemb::template::CredentialTemplate;
+use didkemb::DIDExampleStatic;
+use serde_json::{json, Value};
+
+async fn start() -> SocketAddr {
+    let issuer = Issuer::new(
+        didkemb::issuer_key().unwrap(),
+        didkemb::ISSUER_DID,
+        didkemb::VERIFICATION_METHOD,
+        Arc::new(DIDExampleStatic),
+    )
+    .with_templates(CredentialTemplate::load_dir("templates").unwrap());
+    let (addr, server) = didkemb::http::serve(([127, 0, 0, 1], 0).into(), issuer).unwrap();
+    tokio::spawn(server);
+    addr
+}
+
+async fn issue(addr: SocketAddr, body: Value) -> reqwest::Response {
+    reqwest::Client::new()
+        .post(format!("http://{}/credentials/issue", addr))
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn issued_credential_verifies() {
+    let addr = start().await;
+    let res = issue(
+        addr,
+        json!({
+            "subject": "did:example:device1",
+            "claims": { "claim1": "value 1", "claim2": "value 2" }
+        }),
+    )
+    .await;
+    assert_eq!(res.status(), 200);
+    let IssueResponse { jwt } = res.json().await.unwrap();
+
+    let mut context_loader = ssi::jsonld::ContextLoader::default();
+    let report = report::verify_jwt(&jwt, None, &DIDExampleStatic, &mut context_loader).await;
+    assert!(report.verified, "{}", report);
+    let vc = didkemb::decode_jwt(&jwt, &didkemb::issuer_key().unwrap()).unwrap();
+    let subject = serde_json::to_value(&vc.credential_subject).unwrap();
+    assert_eq!(subject["id"], "did:example:device1");
+    assert_eq!(subject["claim2"], "value 2");
+}
+
+#[tokio::test]
+async fn template_fields_are_merged_with_claims() {
+    let addr = start().await;
+    let res = issue(
+        addr,
+        json!({
+            "template": "sensor-reading",
+            "subject": "did:example:device1",
+            "claims": { "reading": "21.5" }
+        }),
+    )
+    .await;
+    assert_eq!(res.status(), 200);
+    let IssueResponse { jwt } = res.json().await.unwrap();
+    let vc = didkemb::decode_jwt(&jwt, &didkemb::issuer_key().unwrap()).unwrap();
+    assert!(vc.type_.contains(&"SensorReading".to_string()));
+    let subject = serde_json::to_value(&vc.credential_subject).unwrap();
+    assert_eq!(subject["unit"], "celsius");
+    assert_eq!(subject["reading"], "21.5");
+}
+
+#[tokio::test]
+async fn claims_only_fill_template_inputs() {
+    let addr = start().await;
+    let res = issue(
+        addr,
+        json!({
+            "template": "benchmark",
+            "subject": "did:example:device1",
+            "claims": {
+                "claims": "100000000",
+                "uuid": "fixed",
+                "now": "2000-01-01T00:00:00Z",
+                "issuer": "did:example:evil",
+            }
+        }),
+    )
+    .await;
+    assert_eq!(res.status(), 200);
+    let IssueResponse { jwt } = res.json().await.unwrap();
+    let vc = didkemb::decode_jwt(&jwt, &didkemb::issuer_key().unwrap()).unwrap();
+    let vc = serde_json::to_value(&vc).unwrap();
+    assert_eq!(vc["issuer"], didkemb::ISSUER_DID);
+    assert_ne!(vc["issuanceDate"], "2000-01-01T00:00:00Z");
+    let subject = &vc["credentialSubject"];
+    assert_eq!(subject["claim10"], "value 10");
+    assert!(subject.get("claim11").is_none());
+}
+
+#[tokio::test]
+async fn bad_requests_are_rejected() {
+    let addr = start().await;
+    let res = issue(
+        addr,
+        json!({ "subject": "did:example:device1", "template": "nope" }),
+    )
+    .await;
+    assert_eq!(res.status(), 400);
+    let body: Value = res.json().await.unwrap();
+    assert!(body["error"].as_str().unwrap().contains("Unknown template"));
+
+    let res = issue(addr, json!({ "claims": {} })).await;
+    assert_eq!(res.status(), 400);
+}
+
+#[tokio::test]
+async fn metrics_count_requests_and_errors() {
+    let addr = start().await;
+    issue(addr, json!({ "subject": "did:example:device1" })).await;
+    issue(addr, json!({})).await;
+    let metrics: Value = reqwest::get(format!("http://{}/metrics", addr))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let route = &metrics["POST /credentials/issue"];
+    assert_eq!(route["requests"], 2);
+    assert_eq!(route["errors"], 1);
+    assert!(route["meanMs"].as_f64().unwrap() > 0.0);
+}
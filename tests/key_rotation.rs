@@ -0,0 +1,204 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use didkemb::document::{DocumentBuilder, KeyType, Relationship};
+use didkemb::issuer::{IssueRequest, Issuer};
+use didkemb::report::{CheckKind, Outcome};
+use didkemb::resolver::versioned::{NEXT_VERSION_ID, VERSION_ID};
+use didkemb::resolver::{DocumentHistory, VersionedResolver};
+use didkemb::verifier::Verifier;
+use serde_json::Value;
+use ssi::jwk::JWK;
+use ssi_dids::did_resolve::{DIDResolver, Metadata, ResolutionInputMetadata, ERROR_NOT_FOUND};
+
+const DID: &str = "did:example:gateway";
+
+fn document(key: &JWK, fragment: &str) -> Value {
+    let mut builder = DocumentBuilder::new(DID);
+    builder
+        .add_key(
+            fragment,
+            key,
+            KeyType::Ed25519VerificationKey2020,
+            &[Relationship::Authentication, Relationship::AssertionMethod],
+        )
+        .unwrap();
+    builder.build()
+}
+
+fn request() -> IssueRequest {
+    IssueRequest {
+        subject: "did:example:device1".to_string(),
+        claims: Default::default(),
+        template: None,
+    }
+}
+
+fn property(meta: &Option<ssi_dids::did_resolve::DocumentMetadata>, name: &str) -> Option<String> {
+    match meta.as_ref()?.property_set.as_ref()?.get(name)? {
+        Metadata::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+#[tokio::test]
+async fn resolves_by_version_time_and_id() {
+    let t1 = Utc::now() - Duration::hours(2);
+    let t2 = Utc::now() - Duration::hours(1);
+    let mut history = DocumentHistory::new(DID);
+    history
+        .push(document(&didkemb::issuer_key().unwrap(), "key-1"), t1)
+        .unwrap();
+    history
+        .push(document(&JWK::generate_ed25519().unwrap(), "key-2"), t2)
+        .unwrap();
+    // Versions only move forward, and only for this DID.
+    assert!(history
+        .push(document(&didkemb::issuer_key().unwrap(), "key-3"), t1)
+        .is_err());
+    let resolver = VersionedResolver::new();
+    resolver.insert(history);
+
+    let at = |time: chrono::DateTime<Utc>| ResolutionInputMetadata {
+        version_time: Some(time.to_rfc3339()),
+        ..Default::default()
+    };
+    let (_, doc, meta) = resolver.resolve(DID, &at(t1 + Duration::minutes(1))).await;
+    assert!(doc.is_some());
+    assert_eq!(property(&meta, VERSION_ID).as_deref(), Some("1"));
+    assert_eq!(property(&meta, NEXT_VERSION_ID).as_deref(), Some("2"));
+    assert_eq!(meta.unwrap().updated, Some(t1));
+
+    let (_, _, meta) = resolver
+        .resolve(DID, &ResolutionInputMetadata::default())
+        .await;
+    assert_eq!(property(&meta, VERSION_ID).as_deref(), Some("2"));
+    assert_eq!(property(&meta, NEXT_VERSION_ID), None);
+
+    let by_id = ResolutionInputMetadata {
+        version_id: Some("1".to_string()),
+        ..Default::default()
+    };
+    let (_, _, meta) = resolver.resolve(DID, &by_id).await;
+    assert_eq!(meta.unwrap().created, Some(t1));
+
+    let (meta, doc, _) = resolver.resolve(DID, &at(t1 - Duration::minutes(1))).await;
+    assert!(doc.is_none());
+    assert_eq!(meta.error.as_deref(), Some(ERROR_NOT_FOUND));
+}
+
+#[tokio::test]
+async fn credentials_signed_before_rotation_still_verify() {
+    let old_key = didkemb::issuer_key().unwrap();
+    let new_key = JWK::generate_ed25519().unwrap();
+    let resolver = Arc::new(VersionedResolver::new());
+    resolver
+        .publish(document(&old_key, "key-1"), Utc::now() - Duration::hours(1))
+        .unwrap();
+
+    let mut issuer = Issuer::new(
+        old_key.clone(),
+        DID,
+        format!("{}#key-1", DID),
+        resolver.clone(),
+    );
+    let old_jwt = issuer.issue(&request()).await.unwrap();
+
+    resolver
+        .publish(document(&new_key, "key-2"), Utc::now())
+        .unwrap();
+    // The retired key can no longer issue...
+    assert!(issuer.issue(&request()).await.is_err());
+    issuer.rotate(new_key, format!("{}#key-2", DID));
+    let new_jwt = issuer.issue(&request()).await.unwrap();
+
+    // ...but what it signed before the rotation is checked against version 1.
+    let verifier = Verifier::new(resolver.clone());
+    let report = verifier.verify(&old_jwt).await;
+    assert!(report.verified, "{}", report);
+    assert_eq!(report.document_version.as_deref(), Some("1"));
+    let report = verifier.verify(&new_jwt).await;
+    assert!(report.verified, "{}", report);
+    assert_eq!(report.document_version.as_deref(), Some("2"));
+}
+
+#[tokio::test]
+async fn retired_key_cannot_sign_after_rotation() {
+    let old_key = didkemb::issuer_key().unwrap();
+    let v1 = document(&old_key, "key-1");
+    let resolver = Arc::new(VersionedResolver::new());
+    resolver
+        .publish(v1.clone(), Utc::now() - Duration::hours(2))
+        .unwrap();
+    resolver
+        .publish(
+            document(&JWK::generate_ed25519().unwrap(), "key-2"),
+            Utc::now() - Duration::hours(1),
+        )
+        .unwrap();
+
+    // Someone holding the old key signs against a stale copy of the document.
+    let stale = Arc::new(VersionedResolver::new());
+    stale.publish(v1, Utc::now() - Duration::hours(2)).unwrap();
+    let jwt = Issuer::new(old_key, DID, format!("{}#key-1", DID), stale)
+        .issue(&request())
+        .await
+        .unwrap();
+
+    let report = Verifier::new(resolver).verify(&jwt).await;
+    assert!(!report.verified);
+    assert_eq!(report.document_version.as_deref(), Some("2"));
+    assert_eq!(
+        report
+            .get(CheckKind::VerificationRelationship)
+            .unwrap()
+            .outcome,
+        Outcome::Failed
+    );
+}
+
+#[tokio::test]
+async fn deactivated_issuer_cannot_backdate_credentials() {
+    let key = didkemb::issuer_key().unwrap();
+    let resolver = Arc::new(VersionedResolver::new());
+    resolver
+        .publish(document(&key, "key-1"), Utc::now() - Duration::hours(2))
+        .unwrap();
+    assert_eq!(
+        resolver
+            .deactivate(DID, Utc::now() - Duration::minutes(30))
+            .unwrap(),
+        "2"
+    );
+    let (_, _, meta) = resolver
+        .resolve(DID, &ResolutionInputMetadata::default())
+        .await;
+    assert_eq!(meta.unwrap().deactivated, Some(true));
+    let mut history = resolver.history(DID).unwrap();
+    assert!(history.is_deactivated());
+    assert!(history.push(document(&key, "key-2"), Utc::now()).is_err());
+
+    // The key signs a credential dated before the deactivation.
+    let mut vc = serde_json::to_value(didkemb::example_credential(3).unwrap()).unwrap();
+    vc["issuer"] = DID.into();
+    vc["issuanceDate"] = (Utc::now() - Duration::hours(1)).to_rfc3339().into();
+    let options = ssi::vc::LinkedDataProofOptions {
+        verification_method: Some(ssi::vc::URI::String(format!("{}#key-1", DID))),
+        ..didkemb::proof_options()
+    };
+    let stale = VersionedResolver::new();
+    stale
+        .publish(document(&key, "key-1"), Utc::now() - Duration::hours(2))
+        .unwrap();
+    let jwt = didkemb::issue_jwt(&serde_json::from_value(vc).unwrap(), &key, &options, &stale)
+        .await
+        .unwrap();
+
+    let report = Verifier::new(resolver).verify(&jwt).await;
+    assert!(!report.verified);
+    assert_eq!(report.document_version.as_deref(), Some("1"));
+    assert_eq!(
+        report.get(CheckKind::IssuerActive).unwrap().outcome,
+        Outcome::Failed
+    );
+}
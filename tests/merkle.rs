@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use didkemb::digest::HashAlgorithm;
+use didkemb::issuer::Issuer;
+use didkemb::merkle::{self, MerkleTree, Side};
+use didkemb::mqtt::SensorReading;
+use didkemb::report::{self, CheckKind, VerificationReport};
+use didkemb::DIDExampleStatic;
+
+fn issuer() -> Issuer {
+    Issuer::new(
+        didkemb::issuer_key().unwrap(),
+        didkemb::ISSUER_DID,
+        didkemb::VERIFICATION_METHOD,
+        Arc::new(DIDExampleStatic),
+    )
+}
+
+fn readings(n: u64) -> Vec<SensorReading> {
+    (0..n)
+        .map(|i| SensorReading::now("did:example:thermo1", i, 20.0 + i as f64, "celsius"))
+        .collect()
+}
+
+async fn verify(jwt: &str, record: &[u8], proof: &merkle::InclusionProof) -> VerificationReport {
+    let mut context_loader = ssi::jsonld::ContextLoader::default();
+    report::verify_jwt_with_inclusion(
+        jwt,
+        record,
+        proof,
+        None,
+        &DIDExampleStatic,
+        &mut context_loader,
+    )
+    .await
+}
+
+#[test]
+fn every_leaf_proves_to_the_root() {
+    for n in 1..=9 {
+        let records: Vec<Vec<u8>> = (0..n).map(|i| vec![i as u8]).collect();
+        for algorithm in HashAlgorithm::ALL {
+            let tree = MerkleTree::new(algorithm, &records).unwrap();
+            for (i, record) in records.iter().enumerate() {
+                let proof = tree.proof(i).unwrap();
+                assert_eq!(proof.root(algorithm, record).unwrap(), tree.root());
+            }
+            assert!(tree.proof(n).is_none());
+        }
+    }
+}
+
+#[test]
+fn proof_cannot_be_moved_to_another_index() {
+    let records: Vec<Vec<u8>> = (0..4).map(|i| vec![i]).collect();
+    let tree = MerkleTree::new(HashAlgorithm::Sha256, &records).unwrap();
+    let mut proof = tree.proof(0).unwrap();
+    assert_eq!(proof.path[0].side, Side::Right);
+    proof.index = 1;
+    assert!(proof.root(HashAlgorithm::Sha256, &records[0]).is_err());
+}
+
+#[tokio::test]
+async fn single_reading_verifies_against_signed_root() {
+    let readings = readings(5);
+    let batch = merkle::issue_batch(
+        &issuer(),
+        "did:example:thermo1",
+        &readings,
+        HashAlgorithm::Blake3,
+    )
+    .await
+    .unwrap();
+    assert_eq!(batch.proofs.len(), 5);
+
+    for (reading, proof) in readings.iter().zip(&batch.proofs) {
+        let record = serde_json::to_vec(reading).unwrap();
+        let report = verify(&batch.jwt, &record, proof).await;
+        assert!(report.verified, "{}", report);
+    }
+}
+
+#[tokio::test]
+async fn altered_reading_is_rejected() {
+    let mut readings = readings(5);
+    let batch = merkle::issue_batch(
+        &issuer(),
+        "did:example:thermo1",
+        &readings,
+        HashAlgorithm::Sha256,
+    )
+    .await
+    .unwrap();
+
+    readings[3].value += 0.5;
+    let record = serde_json::to_vec(&readings[3]).unwrap();
+    let report = verify(&batch.jwt, &record, &batch.proofs[3]).await;
+    assert!(!report.verified);
+    assert_eq!(report.failure().unwrap().check, CheckKind::Inclusion);
+
+    let record = serde_json::to_vec(&readings[2]).unwrap();
+    let report = verify(&batch.jwt, &record, &batch.proofs[3]).await;
+    assert_eq!(report.failure().unwrap().check, CheckKind::Inclusion);
+}
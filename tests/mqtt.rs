@@ -0,0 +1,122 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use didkemb::datamodel::DataModel;
+use didkemb::fuzz::Tamper;
+use didkemb::issuer::Issuer;
+use didkemb::mqtt::{self, SensorPublisher, SensorReading, VerifyingSubscriber};
+use didkemb::verifier::Verifier;
+use didkemb::DIDExampleStatic;
+use rumqttc::{AsyncClient, QoS};
+
+async fn broker() -> SocketAddr {
+    let (addr, broker) = mqtt::serve(([127, 0, 0, 1], 0).into()).await.unwrap();
+    tokio::spawn(broker);
+    addr
+}
+
+fn publisher(broker: SocketAddr, id: &str, topic: &str) -> SensorPublisher {
+    let issuer = Issuer::new(
+        didkemb::issuer_key().unwrap(),
+        didkemb::ISSUER_DID,
+        didkemb::VERIFICATION_METHOD,
+        Arc::new(DIDExampleStatic),
+    );
+    SensorPublisher::connect(mqtt::options(id, broker), issuer, topic)
+}
+
+async fn subscriber(broker: SocketAddr, id: &str, filter: &str) -> VerifyingSubscriber {
+    let verifier = Verifier::new(Arc::new(DIDExampleStatic));
+    VerifyingSubscriber::connect(mqtt::options(id, broker), filter, verifier)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn readings_are_signed_published_and_verified() {
+    let broker = broker().await;
+    let mut subscriber = subscriber(broker, "verifier", "sensors/#").await;
+    let publisher = publisher(broker, "sensor", "sensors/kitchen");
+
+    let mut sent = Vec::new();
+    for sequence in 0..5 {
+        let reading = SensorReading::now("did:example:thermo1", sequence, 21.5, "celsius");
+        publisher.publish(&reading).await.unwrap();
+        sent.push(reading);
+    }
+    for reading in sent {
+        let message = subscriber.next().await.unwrap();
+        assert_eq!(message.topic, "sensors/kitchen");
+        assert!(message.report.verified, "{}", message.report);
+        assert_eq!(message.reading.as_ref(), Some(&reading));
+        assert!(message.latency.is_some());
+    }
+}
+
+#[tokio::test]
+async fn tampered_message_is_rejected() {
+    let broker = broker().await;
+    let mut subscriber = subscriber(broker, "verifier", mqtt::DEFAULT_TOPIC).await;
+
+    let vc = didkemb::example_credential(10).unwrap();
+    let jwt = Tamper::FlippedPayload
+        .apply(&vc, &didkemb::issuer_key().unwrap())
+        .await
+        .unwrap();
+    let (client, mut eventloop) = AsyncClient::new(mqtt::options("attacker", broker), 10);
+    tokio::spawn(async move { while eventloop.poll().await.is_ok() {} });
+    client
+        .publish(mqtt::DEFAULT_TOPIC, QoS::AtLeastOnce, false, jwt)
+        .await
+        .unwrap();
+
+    let message = subscriber.next().await.unwrap();
+    assert!(!message.report.verified);
+    assert!(message.report.failure().is_some());
+}
+
+#[tokio::test]
+async fn broker_fans_out_to_matching_subscribers_only() {
+    let broker = broker().await;
+    let mut first = subscriber(broker, "first", "sensors/+").await;
+    let mut second = subscriber(broker, "second", "sensors/garage").await;
+    let garage = publisher(broker, "garage", "sensors/garage");
+
+    let reading = SensorReading::now("did:example:thermo2", 0, 4.0, "celsius");
+    let jwt = garage.publish(&reading).await.unwrap();
+    assert_eq!(first.next().await.unwrap().jwt, jwt);
+    assert_eq!(second.next().await.unwrap().jwt, jwt);
+
+    let attic = publisher(broker, "attic", "sensors/attic");
+    attic
+        .publish(&SensorReading::now(
+            "did:example:thermo3",
+            0,
+            30.0,
+            "celsius",
+        ))
+        .await
+        .unwrap();
+    let message = first.next().await.unwrap();
+    assert_eq!(message.topic, "sensors/attic");
+    let nothing = tokio::time::timeout(std::time::Duration::from_millis(300), second.next()).await;
+    assert!(nothing.is_err());
+}
+
+#[tokio::test]
+async fn readings_decode_from_v2_envelopes() {
+    let issuer = Issuer::new(
+        didkemb::issuer_key().unwrap(),
+        didkemb::ISSUER_DID,
+        didkemb::VERIFICATION_METHOD,
+        Arc::new(DIDExampleStatic),
+    )
+    .with_data_model(DataModel::V2);
+    let reading = SensorReading::now("did:example:thermo1", 7, 21.5, "celsius");
+    let jwt = issuer
+        .issue(&reading.issue_request(None).unwrap())
+        .await
+        .unwrap();
+    assert!(didkemb::datamodel::is_envelope(&jwt));
+    assert_eq!(SensorReading::from_jwt(&jwt).unwrap(), reading);
+}
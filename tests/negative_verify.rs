@@ -0,0 +1,69 @@
+use didkemb::fuzz::{self, Tamper};
+
+async fn assert_rejected(tamper: Tamper) {
+    let key = didkemb::issuer_key().unwrap();
+    let vc = didkemb::example_credential(10).unwrap();
+    if let Err(e) = tamper.check(&vc, &key).await {
+        panic!("{}", e);
+    }
+}
+
+#[tokio::test]
+async fn untampered_credential_verifies() {
+    let key = didkemb::issuer_key().unwrap();
+    let jwt = fuzz::sign(&didkemb::example_credential(10).unwrap(), &key)
+        .await
+        .unwrap();
+    let report = fuzz::verify(&jwt).await;
+    assert!(report.verified, "{}", report);
+}
+
+#[tokio::test]
+async fn flipped_payload_is_rejected() {
+    assert_rejected(Tamper::FlippedPayload).await;
+}
+
+#[tokio::test]
+async fn swapped_signature_is_rejected() {
+    assert_rejected(Tamper::SwappedSignature).await;
+}
+
+#[tokio::test]
+async fn wrong_key_is_rejected() {
+    assert_rejected(Tamper::WrongKey).await;
+}
+
+#[tokio::test]
+async fn unknown_kid_is_rejected() {
+    assert_rejected(Tamper::UnknownKid).await;
+}
+
+#[tokio::test]
+async fn alg_none_is_rejected() {
+    assert_rejected(Tamper::AlgNone).await;
+}
+
+#[tokio::test]
+async fn unresolvable_issuer_is_rejected() {
+    assert_rejected(Tamper::UnresolvableIssuer).await;
+}
+
+#[tokio::test]
+async fn truncated_token_is_rejected() {
+    assert_rejected(Tamper::Truncated).await;
+}
+
+#[tokio::test]
+async fn every_truncation_is_rejected() {
+    let key = didkemb::issuer_key().unwrap();
+    let jwt = fuzz::sign(&didkemb::example_credential(1).unwrap(), &key)
+        .await
+        .unwrap();
+    for len in 0..jwt.len() {
+        assert!(
+            !fuzz::verify(&fuzz::truncate(&jwt, len)).await.verified,
+            "truncated to {} bytes verified",
+            len
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use didkemb::fuzz::{self, Tamper};
+use didkemb::report::{self, CheckKind, Outcome, VerificationReport};
+use didkemb::DIDExampleStatic;
+
+async fn verify(jwt: &str) -> VerificationReport {
+    let mut context_loader = ssi::jsonld::ContextLoader::default();
+    report::verify_jwt(jwt, None, &DIDExampleStatic, &mut context_loader).await
+}
+
+async fn tampered(tamper: Tamper) -> VerificationReport {
+    let vc = didkemb::example_credential(10).unwrap();
+    verify(
+        &tamper
+            .apply(&vc, &didkemb::issuer_key().unwrap())
+            .await
+            .unwrap(),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn valid_credential_passes_every_check() {
+    let jwt = fuzz::sign(
+        &didkemb::example_credential(10).unwrap(),
+        &didkemb::issuer_key().unwrap(),
+    )
+    .await
+    .unwrap();
+    let report = verify(&jwt).await;
+    assert!(report.verified, "{}", report);
+    assert_eq!(report.issuer.as_deref(), Some(didkemb::ISSUER_DID));
+    for check in [
+        CheckKind::Format,
+        CheckKind::IssuerResolution,
+        CheckKind::VerificationRelationship,
+        CheckKind::Signature,
+        CheckKind::Dates,
+    ] {
+        assert_eq!(report.get(check).unwrap().outcome, Outcome::Passed);
+    }
+    assert_eq!(
+        report.get(CheckKind::Status).unwrap().outcome,
+        Outcome::Skipped
+    );
+}
+
+#[tokio::test]
+async fn failures_are_attributed_to_the_right_check() {
+    let cases = [
+        (Tamper::FlippedPayload, CheckKind::Signature),
+        (Tamper::SwappedSignature, CheckKind::Signature),
+        (Tamper::WrongKey, CheckKind::Signature),
+        (Tamper::AlgNone, CheckKind::Signature),
+        (Tamper::UnknownKid, CheckKind::VerificationRelationship),
+        (Tamper::UnresolvableIssuer, CheckKind::IssuerResolution),
+        (Tamper::Truncated, CheckKind::Format),
+    ];
+    for (tamper, check) in cases {
+        let report = tampered(tamper).await;
+        assert!(!report.verified, "{} verified", tamper.name());
+        assert_eq!(report.failure().unwrap().check, check, "{}", tamper.name());
+    }
+}
+
+#[tokio::test]
+async fn expired_credential_fails_dates() {
+    let mut vc = didkemb::example_credential(1).unwrap();
+    vc.expiration_date = Some("2020-01-01T00:00:00Z".parse().unwrap());
+    let jwt = fuzz::sign(&vc, &didkemb::issuer_key().unwrap())
+        .await
+        .unwrap();
+    let report = verify(&jwt).await;
+    assert!(!report.verified);
+    assert_eq!(report.failure().unwrap().check, CheckKind::Dates);
+}
+
+#[tokio::test]
+async fn report_serializes_to_json() {
+    let report = tampered(Tamper::WrongKey).await;
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["verified"], false);
+    assert_eq!(json["checks"][4]["check"], "signature");
+    assert_eq!(json["checks"][4]["outcome"], "failed");
+    assert_eq!(json["checks"][5]["outcome"], "skipped");
+}
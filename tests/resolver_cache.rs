@@ -0,0 +1,199 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use didkemb::document::{DocumentBuilder, KeyType, Relationship};
+use didkemb::resolver::versioned::VERSION_ID;
+use didkemb::resolver::{CachingResolver, StaticResolver, VersionedResolver};
+use didkemb::DIDExampleStatic;
+use serde_json::Value;
+use ssi_dids::did_resolve::{
+    DIDResolver, DocumentMetadata, Metadata, ResolutionInputMetadata, ResolutionMetadata,
+    TYPE_DID_LD_JSON,
+};
+use ssi_dids::Document;
+
+/// Counts calls reaching the wrapped resolver.
+#[derive(Default)]
+struct Counting {
+    calls: AtomicUsize,
+}
+
+#[async_trait]
+impl DIDResolver for Counting {
+    async fn resolve(
+        &self,
+        did: &str,
+        input_metadata: &ResolutionInputMetadata,
+    ) -> (
+        ResolutionMetadata,
+        Option<Document>,
+        Option<DocumentMetadata>,
+    ) {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        DIDExampleStatic.resolve(did, input_metadata).await
+    }
+}
+
+fn document(did: &str, fragment: &str) -> Value {
+    let mut builder = DocumentBuilder::new(did);
+    builder
+        .add_key(
+            fragment,
+            &didkemb::issuer_key().unwrap(),
+            KeyType::Ed25519VerificationKey2020,
+            &[Relationship::AssertionMethod],
+        )
+        .unwrap();
+    builder.build()
+}
+
+async fn resolve(resolver: &dyn DIDResolver, did: &str) -> bool {
+    let (meta, doc, _) = resolver
+        .resolve(did, &ResolutionInputMetadata::default())
+        .await;
+    meta.error.is_none() && doc.is_some()
+}
+
+#[tokio::test]
+async fn hits_skip_the_wrapped_resolver() {
+    let cache = CachingResolver::new(Counting::default(), Duration::from_secs(60), 8);
+    for _ in 0..5 {
+        assert!(resolve(&cache, didkemb::ISSUER_DID).await);
+    }
+    assert_eq!(cache.inner().calls.load(Ordering::Relaxed), 1);
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.misses), (4, 1));
+    assert!(stats.hit_rate() > 0.7);
+}
+
+#[tokio::test]
+async fn entries_expire_after_ttl() {
+    let cache = CachingResolver::new(Counting::default(), Duration::from_millis(20), 8);
+    assert!(resolve(&cache, didkemb::ISSUER_DID).await);
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    assert!(resolve(&cache, didkemb::ISSUER_DID).await);
+    assert_eq!(cache.stats().misses, 2);
+}
+
+#[tokio::test]
+async fn errors_are_not_cached_and_no_cache_bypasses() {
+    let cache = CachingResolver::new(Counting::default(), Duration::from_secs(60), 8);
+    assert!(!resolve(&cache, "did:example:missing").await);
+    assert!(!resolve(&cache, "did:example:missing").await);
+    assert!(cache.is_empty());
+    assert_eq!(cache.stats().misses, 2);
+
+    assert!(resolve(&cache, didkemb::ISSUER_DID).await);
+    let no_cache = ResolutionInputMetadata {
+        no_cache: Some(true),
+        ..Default::default()
+    };
+    cache.resolve(didkemb::ISSUER_DID, &no_cache).await;
+    assert_eq!(cache.inner().calls.load(Ordering::Relaxed), 4);
+}
+
+#[tokio::test]
+async fn hits_keep_the_metadata_and_deactivations_are_not_cached() {
+    let resolver = StaticResolver::pre_provisioned();
+    assert!(resolver.deactivate(didkemb::ISSUER_DID));
+    let cache = CachingResolver::new(resolver, Duration::from_secs(60), 8);
+    for _ in 0..2 {
+        let (_, doc, meta) = cache
+            .resolve(didkemb::ISSUER_DID, &ResolutionInputMetadata::default())
+            .await;
+        assert!(doc.is_some());
+        assert_eq!(meta.unwrap().deactivated, Some(true));
+    }
+    assert!(cache.is_empty());
+    assert_eq!(cache.stats().misses, 2);
+
+    let versions = VersionedResolver::new();
+    versions
+        .publish(document("did:example:gateway", "key-1"), Utc::now())
+        .unwrap();
+    let cache = CachingResolver::new(versions, Duration::from_secs(60), 8);
+    for _ in 0..2 {
+        let (meta, _, doc_meta) = cache
+            .resolve("did:example:gateway", &ResolutionInputMetadata::default())
+            .await;
+        assert_eq!(meta.content_type.as_deref(), Some(TYPE_DID_LD_JSON));
+        assert!(doc_meta.unwrap().created.is_some());
+    }
+    assert_eq!(cache.stats().hits, 1);
+}
+
+#[tokio::test]
+async fn least_recently_used_entry_is_evicted() {
+    let cache = CachingResolver::new(DIDExampleStatic, Duration::from_secs(60), 1);
+    let versioned = ResolutionInputMetadata {
+        version_id: Some("1".to_string()),
+        ..Default::default()
+    };
+    assert!(resolve(&cache, didkemb::ISSUER_DID).await);
+    cache.resolve(didkemb::ISSUER_DID, &versioned).await;
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.stats().evictions, 1);
+}
+
+#[tokio::test]
+async fn verification_through_the_cache() {
+    let cache = CachingResolver::new(Counting::default(), Duration::from_secs(60), 8);
+    let key = didkemb::issuer_key().unwrap();
+    let mut context_loader = didkemb::context::loader();
+    // Each credential is resolved as of its own issuance date.
+    for claims in [3, 10] {
+        let vc = didkemb::example_credential(claims).unwrap();
+        let jwt = didkemb::fuzz::sign(&vc, &key).await.unwrap();
+        let report = didkemb::report::verify_jwt(&jwt, None, &cache, &mut context_loader).await;
+        assert!(report.verified, "{}", report);
+    }
+    assert_eq!(cache.inner().calls.load(Ordering::Relaxed), 1);
+    assert_eq!(cache.stats().misses, 1);
+    assert_eq!(cache.len(), 1);
+}
+
+#[tokio::test]
+async fn version_time_is_answered_by_the_version_in_effect() {
+    let did = "did:example:gateway";
+    let t1 = Utc::now() - ChronoDuration::hours(2);
+    let t2 = Utc::now() - ChronoDuration::hours(1);
+    let versions = VersionedResolver::new();
+    versions.publish(document(did, "key-1"), t1).unwrap();
+    versions.publish(document(did, "key-2"), t2).unwrap();
+    let cache = CachingResolver::new(versions, Duration::from_secs(60), 8);
+    let at = |time: DateTime<Utc>| ResolutionInputMetadata {
+        version_time: Some(time.to_rfc3339()),
+        ..Default::default()
+    };
+    let version = |meta: Option<DocumentMetadata>| match meta
+        .and_then(|m| m.property_set)
+        .and_then(|mut p| p.remove(VERSION_ID))
+    {
+        Some(Metadata::String(id)) => id,
+        other => panic!("{:?}", other),
+    };
+
+    let (_, _, meta) = cache
+        .resolve(did, &ResolutionInputMetadata::default())
+        .await;
+    assert_eq!(version(meta), "2");
+    for minutes in [1, 2] {
+        let (_, _, meta) = cache
+            .resolve(did, &at(t2 + ChronoDuration::minutes(minutes)))
+            .await;
+        assert_eq!(version(meta), "2");
+    }
+    assert_eq!((cache.stats().hits, cache.stats().misses), (2, 1));
+
+    let (_, _, meta) = cache
+        .resolve(did, &at(t1 + ChronoDuration::minutes(1)))
+        .await;
+    assert_eq!(version(meta), "1");
+    let (_, _, meta) = cache
+        .resolve(did, &ResolutionInputMetadata::default())
+        .await;
+    assert_eq!(version(meta), "2");
+    assert_eq!(cache.stats().misses, 3);
+}
@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use didkemb::resolver::{chain::ATTEMPTS, ResolverChain};
+use didkemb::DIDExampleStatic;
+use ssi_dids::did_resolve::{
+    DIDResolver, DocumentMetadata, Metadata, ResolutionInputMetadata, ResolutionMetadata,
+    ERROR_NOT_FOUND,
+};
+use ssi_dids::example::DIDExample;
+use ssi_dids::Document;
+
+/// Always fails with a fixed error.
+struct Broken;
+
+#[async_trait]
+impl DIDResolver for Broken {
+    async fn resolve(
+        &self,
+        _did: &str,
+        _input_metadata: &ResolutionInputMetadata,
+    ) -> (
+        ResolutionMetadata,
+        Option<Document>,
+        Option<DocumentMetadata>,
+    ) {
+        (ResolutionMetadata::from_error("backend down"), None, None)
+    }
+}
+
+/// Reports every DID as deactivated, without a document.
+struct Deactivated;
+
+#[async_trait]
+impl DIDResolver for Deactivated {
+    async fn resolve(
+        &self,
+        _did: &str,
+        _input_metadata: &ResolutionInputMetadata,
+    ) -> (
+        ResolutionMetadata,
+        Option<Document>,
+        Option<DocumentMetadata>,
+    ) {
+        let meta = DocumentMetadata {
+            deactivated: Some(true),
+            ..Default::default()
+        };
+        (ResolutionMetadata::default(), None, Some(meta))
+    }
+}
+
+async fn resolve(chain: &ResolverChain, did: &str) -> (ResolutionMetadata, Option<Document>) {
+    let (meta, doc, _) = chain
+        .resolve(did, &ResolutionInputMetadata::default())
+        .await;
+    (meta, doc)
+}
+
+fn attempts(meta: &ResolutionMetadata) -> Vec<(String, String)> {
+    let Some(Metadata::List(attempts)) = meta.property_set.as_ref().and_then(|p| p.get(ATTEMPTS))
+    else {
+        return Vec::new();
+    };
+    attempts
+        .iter()
+        .map(|a| match a {
+            Metadata::Map(m) => match (m.get("resolver"), m.get("error")) {
+                (Some(Metadata::String(r)), Some(Metadata::String(e))) => (r.clone(), e.clone()),
+                _ => panic!("malformed attempt"),
+            },
+            _ => panic!("malformed attempt"),
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn routes_by_method_and_falls_back() {
+    let chain = ResolverChain::new()
+        .route("example", DIDExample)
+        .fallback(DIDExampleStatic);
+
+    let (meta, doc) = resolve(&chain, "did:example:foo").await;
+    assert!(meta.error.is_none());
+    assert_eq!(doc.unwrap().id, "did:example:foo");
+
+    // did:key is not routed, so only the pre-provisioned fallback sees it.
+    let (meta, doc) = resolve(&chain, didkemb::ISSUER_DID).await;
+    assert!(meta.error.is_none());
+    assert_eq!(doc.unwrap().id, didkemb::ISSUER_DID);
+}
+
+#[tokio::test]
+async fn routed_resolver_only_sees_its_method() {
+    let chain = ResolverChain::new().route("example", DIDExample);
+    let (meta, doc) = resolve(&chain, "did:examplex:foo").await;
+    assert!(doc.is_none());
+    assert_eq!(meta.error.as_deref(), Some(ERROR_NOT_FOUND));
+    assert!(attempts(&meta).is_empty());
+}
+
+#[tokio::test]
+async fn errors_from_every_attempt_are_aggregated() {
+    let chain = ResolverChain::new()
+        .route("key", Broken)
+        .fallback(DIDExample)
+        .fallback(DIDExampleStatic);
+
+    let (meta, doc) = resolve(&chain, "did:key:z6MkUnknown").await;
+    assert!(doc.is_none());
+    assert_eq!(meta.error.as_deref(), Some("backend down"));
+    let attempts = attempts(&meta);
+    assert_eq!(attempts.len(), 3);
+    assert_eq!(
+        attempts[0],
+        ("Broken (did:key)".to_string(), "backend down".to_string())
+    );
+    assert_eq!(attempts[2].0, "DIDExampleStatic");
+
+    // A later resolver still wins over an earlier failure.
+    let (meta, doc) = resolve(&chain, didkemb::ISSUER_DID).await;
+    assert!(meta.error.is_none() && doc.is_some());
+}
+
+#[tokio::test]
+async fn deactivation_stops_the_fallback() {
+    let chain = ResolverChain::new()
+        .route("example", Deactivated)
+        .fallback(DIDExample);
+    let (meta, doc, doc_meta) = chain
+        .resolve("did:example:foo", &ResolutionInputMetadata::default())
+        .await;
+    assert_eq!(meta.error, None);
+    assert!(doc.is_none());
+    assert_eq!(doc_meta.unwrap().deactivated, Some(true));
+}
+
+#[tokio::test]
+async fn default_chain_verifies_the_benchmark_credential() {
+    let chain = didkemb::resolver::default_chain();
+    let vc = didkemb::example_credential(10).unwrap();
+    let jwt = didkemb::fuzz::sign(&vc, &didkemb::issuer_key().unwrap())
+        .await
+        .unwrap();
+    let mut context_loader = ssi::jsonld::ContextLoader::default();
+    let report = didkemb::report::verify_jwt(&jwt, None, &chain, &mut context_loader).await;
+    assert!(report.verified, "{}", report);
+}
@@ -0,0 +1,187 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use didkemb::http::Routes;
+use didkemb::issuer::{IssueRequest, Issuer};
+use didkemb::resolver::web::{did_web_url, HttpClient, ReqwestClient, MAX_BODY};
+use didkemb::resolver::DIDWeb;
+use didkemb::verifier::Verifier;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Response, StatusCode};
+use serde_json::Value;
+use ssi_dids::did_resolve::{DIDResolver, ResolutionInputMetadata, ERROR_NOT_FOUND};
+
+const GATEWAY: &str = "did:web:gateway.example";
+
+/// Serves `tests/fixtures/did-web` as the web root of gateway.example.
+struct Fixtures;
+
+#[async_trait]
+impl Routes for Fixtures {
+    async fn handle(&self, _method: &Method, path: &str, _body: &[u8]) -> (StatusCode, Value) {
+        let file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/did-web")
+            .join(path.trim_start_matches('/'));
+        match std::fs::read(file) {
+            Ok(bytes) => (StatusCode::OK, serde_json::from_slice(&bytes).unwrap()),
+            Err(_) => (StatusCode::NOT_FOUND, Value::Null),
+        }
+    }
+}
+
+/// Sends `https://gateway.example/...` to the local fixture server.
+struct Local {
+    addr: SocketAddr,
+}
+
+#[async_trait]
+impl HttpClient for Local {
+    async fn get(&self, url: &str) -> Result<(u16, Vec<u8>), String> {
+        let url = url.replace(
+            "https://gateway.example/",
+            &format!("http://{}/", self.addr),
+        );
+        let response = reqwest::get(url).await.map_err(|e| e.to_string())?;
+        let status = response.status().as_u16();
+        let body = response.bytes().await.map_err(|e| e.to_string())?;
+        Ok((status, body.to_vec()))
+    }
+}
+
+fn resolver() -> DIDWeb {
+    let (addr, server) =
+        didkemb::http::serve(SocketAddr::from(([127, 0, 0, 1], 0)), Fixtures).unwrap();
+    tokio::spawn(server);
+    DIDWeb::with_client(Local { addr })
+}
+
+#[test]
+fn maps_dids_to_urls() {
+    assert_eq!(
+        did_web_url("did:web:gateway.example").as_deref(),
+        Some("https://gateway.example/.well-known/did.json")
+    );
+    assert_eq!(
+        did_web_url("did:web:localhost%3A8443:devices:sensor1").as_deref(),
+        Some("https://localhost:8443/devices/sensor1/did.json")
+    );
+    for invalid in [
+        "did:key:z6Mk",
+        "did:web:",
+        "did:web:gateway.example::x",
+        "did:web:gateway.example:..",
+        "did:web:gateway.example%2Fevil",
+        "did:web:gateway.example:%zz",
+    ] {
+        assert_eq!(did_web_url(invalid), None, "{}", invalid);
+    }
+}
+
+#[tokio::test]
+async fn resolves_domain_and_path_dids() {
+    let resolver = resolver();
+    for did in [GATEWAY, "did:web:gateway.example:devices:sensor1"] {
+        let (meta, doc, _) = resolver
+            .resolve(did, &ResolutionInputMetadata::default())
+            .await;
+        assert!(meta.error.is_none(), "{:?}", meta.error);
+        assert_eq!(doc.unwrap().id, did);
+    }
+}
+
+#[tokio::test]
+async fn rejects_document_for_another_did() {
+    let resolver = resolver();
+    let (meta, doc, _) = resolver
+        .resolve(
+            "did:web:gateway.example:mine",
+            &ResolutionInputMetadata::default(),
+        )
+        .await;
+    assert!(doc.is_none());
+    assert!(meta.error.unwrap().contains("is for did:key:"));
+
+    let (meta, doc, _) = resolver
+        .resolve(
+            "did:web:gateway.example:devices:missing",
+            &ResolutionInputMetadata::default(),
+        )
+        .await;
+    assert!(doc.is_none());
+    assert_eq!(meta.error.as_deref(), Some(ERROR_NOT_FOUND));
+}
+
+#[tokio::test]
+async fn credential_from_web_issuer_verifies() {
+    let resolver: Arc<DIDWeb> = Arc::new(resolver());
+    let issuer = Issuer::new(
+        didkemb::issuer_key().unwrap(),
+        GATEWAY,
+        format!("{}#key-1", GATEWAY),
+        resolver.clone(),
+    );
+    let jwt = issuer
+        .issue(&IssueRequest {
+            subject: "did:web:gateway.example:devices:sensor1".to_string(),
+            claims: Default::default(),
+            template: None,
+        })
+        .await
+        .unwrap();
+
+    let report = Verifier::new(resolver).verify(&jwt).await;
+    assert!(report.verified, "{}", report);
+}
+
+/// Answers `/redirect` with a redirect, `/large` with a body over
+/// [`MAX_BODY`] and `/stream` with the same body, chunked.
+fn misbehaving() -> SocketAddr {
+    let make = make_service_fn(|_| async {
+        Ok::<_, hyper::Error>(service_fn(|req: hyper::Request<Body>| async move {
+            let large = vec![b' '; MAX_BODY + 1];
+            let response = match req.uri().path() {
+                "/redirect" => Response::builder()
+                    .status(StatusCode::FOUND)
+                    .header("location", "/.well-known/did.json")
+                    .body(Body::empty()),
+                "/large" => Response::builder().body(Body::from(large)),
+                _ => {
+                    let (mut sender, body) = Body::channel();
+                    tokio::spawn(async move {
+                        for chunk in large.chunks(64 * 1024) {
+                            if sender.send_data(chunk.to_vec().into()).await.is_err() {
+                                break;
+                            }
+                        }
+                    });
+                    Response::builder().body(body)
+                }
+            };
+            Ok::<_, hyper::Error>(response.unwrap())
+        }))
+    });
+    let server = hyper::Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make);
+    let addr = server.local_addr();
+    tokio::spawn(server);
+    addr
+}
+
+#[tokio::test]
+async fn default_client_refuses_redirects_and_large_bodies() {
+    let addr = misbehaving();
+    let client = ReqwestClient::default();
+    let (status, _) = client
+        .get(&format!("http://{}/redirect", addr))
+        .await
+        .unwrap();
+    assert_eq!(status, 302);
+    for path in ["large", "stream"] {
+        let error = client
+            .get(&format!("http://{}/{}", addr, path))
+            .await
+            .unwrap_err();
+        assert!(error.starts_with("Response larger than"), "{}", error);
+    }
+}
@@ -0,0 +1,45 @@
+use didkemb::cli::Args;
+use didkemb::measure::ProcessMonitor;
+use didkemb::runtime::Flavor;
+
+#[test]
+fn flavors_round_trip_through_display() {
+    for flavor in [
+        Flavor::CurrentThread,
+        Flavor::MultiThread { workers: None },
+        Flavor::MultiThread { workers: Some(3) },
+    ] {
+        assert_eq!(flavor.to_string().parse::<Flavor>().unwrap(), flavor);
+    }
+    assert!("current_thread:2".parse::<Flavor>().is_err());
+    assert!("multi_thread:0".parse::<Flavor>().is_err());
+    assert!("single".parse::<Flavor>().is_err());
+}
+
+#[test]
+fn flags_select_the_flavor() {
+    let args = Args::new(["--runtime", "current_thread"]);
+    assert_eq!(Flavor::from_args(&args).unwrap(), Flavor::CurrentThread);
+    let args = Args::new(["--workers", "2"]);
+    assert_eq!(
+        Flavor::from_args(&args).unwrap(),
+        Flavor::MultiThread { workers: Some(2) }
+    );
+}
+
+#[test]
+fn current_thread_runtime_signs_and_verifies() {
+    let monitor = ProcessMonitor::current().unwrap();
+    let runtime = Flavor::CurrentThread.build().unwrap();
+    let report = runtime.block_on(async {
+        let vc = didkemb::example_credential(10).unwrap();
+        let jwt = didkemb::fuzz::sign(&vc, &didkemb::issuer_key().unwrap())
+            .await
+            .unwrap();
+        let mut context_loader = ssi::jsonld::ContextLoader::default();
+        didkemb::report::verify_jwt(&jwt, None, &didkemb::DIDExampleStatic, &mut context_loader)
+            .await
+    });
+    assert!(report.verified, "{}", report);
+    assert!(monitor.sample().unwrap().threads >= 1);
+}
@@ -0,0 +1,134 @@
+use chrono::{DateTime, Duration, Utc};
+use didkemb::schema::SchemaRegistry;
+use didkemb::template::{self, CredentialTemplate, Variables};
+use didkemb::Error;
+use serde_json::json;
+
+fn vars(pairs: &[(&str, &str)]) -> Variables {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn benchmark_template_matches_the_claim_binaries() {
+    let vc = CredentialTemplate::benchmark()
+        .render(&vars(&[("claims", "20")]))
+        .unwrap();
+    let vc = serde_json::to_value(&vc).unwrap();
+    assert_eq!(vc["@context"], didkemb::benchmark_context());
+    assert_eq!(vc["type"], json!("VerifiableCredential"));
+    assert_eq!(vc["issuer"], json!(didkemb::ISSUER_DID));
+    let subject = vc["credentialSubject"].as_object().unwrap();
+    assert_eq!(subject.len(), 22);
+    assert!(subject["id"].as_str().unwrap().starts_with("urn:uuid:"));
+    assert_eq!(subject["claim20"], json!("value 20"));
+    assert!(vc.get("expirationDate").is_none());
+}
+
+#[test]
+fn toml_template_fills_placeholders_and_expiration() {
+    let template = CredentialTemplate::load("templates/firmware-attestation.toml").unwrap();
+    let vc = template
+        .render(&vars(&[
+            ("subject", "did:example:thermo1"),
+            ("version", "2.4.1"),
+        ]))
+        .unwrap();
+    let vc = serde_json::to_value(&vc).unwrap();
+    assert_eq!(
+        vc["type"],
+        json!(["VerifiableCredential", "FirmwareAttestation"])
+    );
+    let subject = &vc["credentialSubject"];
+    assert_eq!(subject["id"], json!("did:example:thermo1"));
+    assert_eq!(subject["firmware"], json!("2.4.1"));
+    assert_eq!(subject["digest"], json!("unknown"));
+
+    let date = |key: &str| {
+        DateTime::parse_from_rfc3339(vc[key].as_str().unwrap())
+            .unwrap()
+            .with_timezone(&Utc)
+    };
+    assert_eq!(
+        date("expirationDate") - date("issuanceDate"),
+        Duration::days(90)
+    );
+}
+
+#[test]
+fn json_template_names_its_schema() {
+    let template = CredentialTemplate::load("templates/sensor-reading.json").unwrap();
+    let registry = SchemaRegistry::from_dir("schemas").unwrap();
+
+    let vc = template
+        .render(&vars(&[
+            ("subject", "did:example:device1"),
+            ("reading", "21.5"),
+        ]))
+        .unwrap();
+    assert!(registry.validate(&vc).is_ok());
+
+    let vc = template
+        .render(&vars(&[
+            ("subject", "did:example:device1"),
+            ("reading", "hot"),
+        ]))
+        .unwrap();
+    let errors = registry.validate(&vc).unwrap_err();
+    assert_eq!(errors[0].pointer, "/credentialSubject/reading");
+}
+
+#[test]
+fn missing_variables_and_bad_rules_are_template_errors() {
+    let template = CredentialTemplate::load("templates/firmware-attestation.toml").unwrap();
+    let err = template
+        .render(&vars(&[("subject", "did:example:thermo1")]))
+        .unwrap_err();
+    assert!(matches!(err, Error::Template(ref m) if m.contains("version")));
+
+    let template = CredentialTemplate::parse(
+        r#"
+        issuer = "did:example:issuer"
+        expiration = { after = "{{ttl}}" }
+        "#,
+    )
+    .unwrap();
+    // The last two overflow the duration and the expiration date.
+    for ttl in ["3 weeks", "1é", "", "h", "9999999999999999d", "100000000d"] {
+        assert!(
+            matches!(
+                template.render(&vars(&[("ttl", ttl)])),
+                Err(Error::Template(_))
+            ),
+            "{}",
+            ttl
+        );
+    }
+
+    let err = CredentialTemplate::benchmark()
+        .render(&vars(&[("claims", "100000000")]))
+        .unwrap_err();
+    assert!(matches!(err, Error::Template(ref m) if m.contains("over")));
+}
+
+#[test]
+fn command_line_variables_override_defaults() {
+    let args = didkemb::cli::Args::new([
+        "--template",
+        "templates/benchmark.toml",
+        "--var",
+        "claims=3",
+        "--var",
+        "issuer=did:example:gateway",
+    ]);
+    let (template, picked) = template::from_args(&args, &[("claims", "10")]).unwrap();
+    assert_eq!(picked["claims"], "3");
+    let vc = serde_json::to_value(template.render(&picked).unwrap()).unwrap();
+    assert_eq!(vc["issuer"], json!("did:example:gateway"));
+    assert_eq!(vc["credentialSubject"].as_object().unwrap().len(), 5);
+
+    let parsed = template::parse_variables(r#"{"claims": 7, "unit": "kelvin"}"#).unwrap();
+    assert_eq!(parsed, vars(&[("claims", "7"), ("unit", "kelvin")]));
+}
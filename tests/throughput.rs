@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use std::sync::Arc;
+
+use didkemb::throughput;
+use didkemb::DIDExampleStatic;
+
+#[cfg(feature = "multi-thread")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn concurrent_tasks_issue_and_verify() {
+    let baseline = throughput::run(
+        1,
+        Duration::from_millis(300),
+        10,
+        Arc::new(DIDExampleStatic),
+    )
+    .await
+    .unwrap();
+    let run = throughput::run(
+        2,
+        Duration::from_millis(300),
+        10,
+        Arc::new(DIDExampleStatic),
+    )
+    .await
+    .unwrap();
+    for run in [&baseline, &run] {
+        assert!(run.completed > 0);
+        assert_eq!(run.failed, 0);
+        assert_eq!(run.latency.unwrap().count, run.completed);
+    }
+    assert!(run.efficiency(&baseline) > 0.0);
+}
+
+#[tokio::test]
+async fn zero_tasks_is_an_error() {
+    assert!(
+        throughput::run(0, Duration::from_millis(10), 10, Arc::new(DIDExampleStatic))
+            .await
+            .is_err()
+    );
+}
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use didkemb::fuzz::{self, Tamper};
+use didkemb::identity::{Identity, Method};
+use didkemb::report::{CheckKind, Outcome, VerificationReport};
+use didkemb::resolver;
+use didkemb::trust::TrustRegistry;
+use didkemb::verifier::Verifier;
+use ssi::one_or_many::OneOrMany;
+use ssi::vc::Credential;
+
+fn verifier() -> Verifier {
+    let registry = TrustRegistry::load(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/trust/registry.json"
+    ))
+    .unwrap();
+    Verifier::new(Arc::new(resolver::default_chain())).with_trust_registry(registry)
+}
+
+fn sensor_reading(claims: usize) -> Credential {
+    let mut vc = didkemb::example_credential(claims).unwrap();
+    vc.type_ = OneOrMany::Many(vec![
+        "VerifiableCredential".to_string(),
+        "SensorReading".to_string(),
+    ]);
+    vc
+}
+
+async fn issue(vc: &Credential) -> String {
+    didkemb::issue_jwt(
+        vc,
+        &didkemb::issuer_key().unwrap(),
+        &didkemb::proof_options(),
+        &resolver::default_chain(),
+    )
+    .await
+    .unwrap()
+}
+
+fn outcome(report: &VerificationReport) -> Outcome {
+    report.get(CheckKind::Trust).map(|c| c.outcome).unwrap()
+}
+
+#[tokio::test]
+async fn rule_accepts_allowed_types_and_claims() {
+    let report = verifier().verify(&issue(&sensor_reading(3)).await).await;
+    assert!(report.verified, "{}", report);
+    assert_eq!(outcome(&report), Outcome::Passed);
+    assert_eq!(report.trust_rule.as_deref(), Some("factory-sensors"));
+}
+
+#[tokio::test]
+async fn every_rule_reports_why_it_rejected() {
+    let report = verifier().verify(&issue(&sensor_reading(4)).await).await;
+    assert!(!report.verified);
+    assert_eq!(outcome(&report), Outcome::Failed);
+    assert_eq!(report.trust_rule.as_deref(), Some("retired-sensors"));
+    let reason = report.to_string();
+    assert!(reason.contains("retired-sensors: expired"), "{}", reason);
+    assert!(
+        reason.contains("factory-sensors: claim claim4 not allowed"),
+        "{}",
+        reason
+    );
+}
+
+#[tokio::test]
+async fn backdating_does_not_revive_an_expired_rule() {
+    let mut vc = serde_json::to_value(sensor_reading(3)).unwrap();
+    vc["issuanceDate"] = "2019-06-01T00:00:00Z".into();
+    let vc: Credential = serde_json::from_value(vc).unwrap();
+    let report = verifier().verify(&issue(&vc).await).await;
+    assert!(!report.verified);
+    assert_eq!(outcome(&report), Outcome::Failed);
+    let reason = report.to_string();
+    assert!(reason.contains("retired-sensors: expired"), "{}", reason);
+    assert!(
+        reason.contains("factory-sensors: not in effect before"),
+        "{}",
+        reason
+    );
+}
+
+#[tokio::test]
+async fn unknown_issuer_is_not_trusted() {
+    let identity = Identity::generate(Method::Key).unwrap();
+    let mut vc = sensor_reading(1);
+    vc.issuer = Some(ssi::vc::Issuer::URI(ssi::vc::URI::String(
+        identity.did.clone(),
+    )));
+    let jwt = fuzz::sign_as(&vc, &identity.key, &identity.assertion_method).unwrap();
+    let report = verifier().verify(&jwt).await;
+    assert_eq!(outcome(&report), Outcome::Failed);
+    assert_eq!(report.trust_rule, None);
+    assert!(report.to_string().contains("No trust rule"), "{}", report);
+}
+
+#[tokio::test]
+async fn trust_is_skipped_when_crypto_fails() {
+    let vc = sensor_reading(3);
+    let forged = Tamper::WrongKey
+        .apply(&vc, &didkemb::issuer_key().unwrap())
+        .await
+        .unwrap();
+    let report = verifier().verify(&forged).await;
+    assert!(!report.verified);
+    assert_eq!(outcome(&report), Outcome::Skipped);
+    assert_eq!(report.trust_rule, None);
+}
@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use didkemb::fuzz;
+use didkemb::report::{self, CheckKind, Kind, Outcome, VerificationReport};
+use didkemb::resolver::FileResolver;
+use didkemb::verifier::Verifier;
+use serde_json::json;
+use ssi::jwk::Algorithm;
+use ssi::jws::Header;
+use ssi::vc::{Credential, Presentation, ProofPurpose, URI};
+
+const AUTHENTICATION_ONLY: &str = "did:example:authentication-only";
+const ASSERTION_ONLY: &str = "did:example:assertion-only";
+
+fn resolver() -> FileResolver {
+    FileResolver::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/relationships"
+    ))
+}
+
+fn vm(did: &str) -> String {
+    format!("{}#key-1", did)
+}
+
+fn credential(issuer: &str) -> String {
+    let mut vc: Credential = didkemb::example_credential(3).unwrap();
+    vc.issuer = Some(ssi::vc::Issuer::URI(URI::String(issuer.to_string())));
+    fuzz::sign_as(&vc, &didkemb::issuer_key().unwrap(), &vm(issuer)).unwrap()
+}
+
+/// A presentation by `holder`, signed with its `#key-1` whatever its
+/// relationships.
+fn presentation(holder: &str, vc_jwt: String) -> String {
+    let vp: Presentation = serde_json::from_value(json!({
+        "@context": ["https://www.w3.org/2018/credentials/v1"],
+        "type": "VerifiablePresentation",
+        "holder": holder,
+        "verifiableCredential": vc_jwt,
+    }))
+    .unwrap();
+    let claims = serde_json::to_string(&vp.to_jwt_claims().unwrap()).unwrap();
+    let header = Header {
+        algorithm: Algorithm::EdDSA,
+        key_id: Some(vm(holder)),
+        ..Default::default()
+    };
+    ssi::jws::encode_sign_custom_header(&claims, &didkemb::issuer_key().unwrap(), &header).unwrap()
+}
+
+fn outcome(report: &VerificationReport, check: CheckKind) -> Outcome {
+    report.get(check).map(|c| c.outcome).unwrap()
+}
+
+#[tokio::test]
+async fn credential_signed_with_authentication_key_is_rejected() {
+    let jwt = credential(AUTHENTICATION_ONLY);
+    let report = Verifier::new(Arc::new(resolver())).verify(&jwt).await;
+    assert!(!report.verified);
+    assert_eq!(
+        outcome(&report, CheckKind::VerificationRelationship),
+        Outcome::Failed
+    );
+    assert!(report.to_string().contains("AssertionMethod"), "{}", report);
+    // The signature itself is fine; it is never even checked.
+    assert_eq!(outcome(&report, CheckKind::Signature), Outcome::Skipped);
+
+    // Pinning the verification method does not bypass the relationship.
+    let options = ssi::vc::LinkedDataProofOptions {
+        verification_method: Some(URI::String(vm(AUTHENTICATION_ONLY))),
+        ..Default::default()
+    };
+    let mut context_loader = ssi::jsonld::ContextLoader::default();
+    let report = report::verify_jwt(&jwt, Some(options), &resolver(), &mut context_loader).await;
+    assert_eq!(
+        outcome(&report, CheckKind::VerificationRelationship),
+        Outcome::Failed
+    );
+}
+
+#[tokio::test]
+async fn credential_signed_with_assertion_key_verifies() {
+    let report = Verifier::new(Arc::new(resolver()))
+        .verify(&credential(ASSERTION_ONLY))
+        .await;
+    assert!(report.verified, "{}", report);
+}
+
+#[tokio::test]
+async fn presentation_needs_an_authentication_key() {
+    let verifier = Verifier::new(Arc::new(resolver()));
+
+    let report = verifier
+        .verify(&presentation(
+            AUTHENTICATION_ONLY,
+            credential(ASSERTION_ONLY),
+        ))
+        .await;
+    assert_eq!(report.kind, Kind::Presentation);
+    assert!(report.verified, "{}", report);
+
+    let report = verifier
+        .verify(&presentation(ASSERTION_ONLY, credential(ASSERTION_ONLY)))
+        .await;
+    assert!(!report.verified);
+    assert_eq!(
+        outcome(&report, CheckKind::VerificationRelationship),
+        Outcome::Failed
+    );
+    assert!(report.to_string().contains("Authentication"), "{}", report);
+}
+
+#[test]
+fn proof_options_carry_the_purpose() {
+    assert_eq!(
+        didkemb::proof_options().proof_purpose,
+        Some(ProofPurpose::AssertionMethod)
+    );
+    assert_eq!(
+        didkemb::proof_options_for(ProofPurpose::Authentication).proof_purpose,
+        Some(ProofPurpose::Authentication)
+    );
+}
@@ -0,0 +1,152 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use didkemb::fuzz::{self, Tamper};
+use didkemb::http::MAX_BODY;
+use didkemb::report::{CheckKind, Kind, VerificationReport};
+use didkemb::verifier::{BatchResponse, Verifier};
+use didkemb::DIDExampleStatic;
+use serde_json::{json, Value};
+use ssi::vc::{LinkedDataProofOptions, ProofPurpose, URI};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+async fn start() -> SocketAddr {
+    let verifier = Verifier::new(Arc::new(DIDExampleStatic));
+    let (addr, server) = didkemb::http::serve(([127, 0, 0, 1], 0).into(), verifier).unwrap();
+    tokio::spawn(server);
+    addr
+}
+
+async fn post(addr: SocketAddr, body: Value) -> reqwest::Response {
+    reqwest::Client::new()
+        .post(format!("http://{}/verify", addr))
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn signed_credential() -> String {
+    let vc = didkemb::example_credential(10).unwrap();
+    fuzz::sign(&vc, &didkemb::issuer_key().unwrap())
+        .await
+        .unwrap()
+}
+
+async fn presentation(vc_jwt: String) -> String {
+    let vp: ssi::vc::Presentation = serde_json::from_value(json!({
+        "@context": ["https://www.w3.org/2018/credentials/v1"],
+        "type": "VerifiablePresentation",
+        "holder": didkemb::ISSUER_DID,
+        "verifiableCredential": vc_jwt,
+    }))
+    .unwrap();
+    let options = LinkedDataProofOptions {
+        verification_method: Some(URI::String(didkemb::VERIFICATION_METHOD.to_string())),
+        proof_purpose: Some(ProofPurpose::Authentication),
+        created: None,
+        checks: None,
+        ..Default::default()
+    };
+    vp.generate_jwt(
+        Some(&didkemb::issuer_key().unwrap()),
+        &options,
+        &DIDExampleStatic,
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn single_credential() {
+    let addr = start().await;
+    let res = post(addr, json!({ "jwt": signed_credential().await })).await;
+    assert_eq!(res.status(), 200);
+    let report: VerificationReport = res.json().await.unwrap();
+    assert!(report.verified, "{}", report);
+    assert_eq!(report.kind, Kind::Credential);
+}
+
+#[tokio::test]
+async fn batch_keeps_order_and_flags_bad_items() {
+    let addr = start().await;
+    let vc = didkemb::example_credential(10).unwrap();
+    let key = didkemb::issuer_key().unwrap();
+    let bad = Tamper::WrongKey.apply(&vc, &key).await.unwrap();
+    let jwts = vec![
+        signed_credential().await,
+        bad,
+        "not a jwt".to_string(),
+        signed_credential().await,
+    ];
+    let res = post(addr, json!({ "jwts": jwts })).await;
+    assert_eq!(res.status(), 200);
+    let batch: BatchResponse = res.json().await.unwrap();
+    assert!(!batch.verified);
+    let verified: Vec<bool> = batch.results.iter().map(|r| r.verified).collect();
+    assert_eq!(verified, [true, false, false, true]);
+    assert_eq!(
+        batch.results[1].failure().unwrap().check,
+        CheckKind::Signature
+    );
+    assert_eq!(batch.results[2].failure().unwrap().check, CheckKind::Format);
+}
+
+#[tokio::test]
+async fn presentation_reports_embedded_credentials() {
+    let addr = start().await;
+    let vp = presentation(signed_credential().await).await;
+    let report: VerificationReport = post(addr, json!({ "jwt": vp })).await.json().await.unwrap();
+    assert!(report.verified, "{:?}", report);
+    assert_eq!(report.kind, Kind::Presentation);
+    assert_eq!(report.holder.as_deref(), Some(didkemb::ISSUER_DID));
+    assert_eq!(report.credentials.len(), 1);
+    assert!(report.credentials[0].verified);
+
+    let vc = didkemb::example_credential(10).unwrap();
+    let key = didkemb::issuer_key().unwrap();
+    let bad = Tamper::FlippedPayload.apply(&vc, &key).await.unwrap();
+    let vp = presentation(bad).await;
+    let report: VerificationReport = post(addr, json!({ "jwt": vp })).await.json().await.unwrap();
+    assert!(!report.verified);
+    assert!(!report.credentials[0].verified);
+}
+
+#[tokio::test]
+async fn malformed_body_is_rejected() {
+    let addr = start().await;
+    let res = post(addr, json!({ "token": "x" })).await;
+    assert_eq!(res.status(), 400);
+}
+
+#[tokio::test]
+async fn oversized_body_is_rejected() {
+    let addr = start().await;
+    let res = reqwest::Client::new()
+        .post(format!("http://{}/verify", addr))
+        .body(vec![b' '; MAX_BODY + 1])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 413);
+
+    // Without a Content-Length the limit applies while reading.
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(b"POST /verify HTTP/1.1\r\nhost: verifier\r\ntransfer-encoding: chunked\r\n\r\n")
+        .await
+        .unwrap();
+    let chunk = vec![b' '; 64 * 1024];
+    for _ in 0..=MAX_BODY / chunk.len() {
+        let mut framed = format!("{:x}\r\n", chunk.len()).into_bytes();
+        framed.extend_from_slice(&chunk);
+        framed.extend_from_slice(b"\r\n");
+        if stream.write_all(&framed).await.is_err() {
+            break;
+        }
+    }
+    let mut response = [0; 12];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(&response, b"HTTP/1.1 413");
+}